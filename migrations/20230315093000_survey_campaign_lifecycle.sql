@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS survey_campaign_state (
+	name VARCHAR(30) UNIQUE NOT NULL,
+	ID SERIAL PRIMARY KEY NOT NULL
+);
+
+INSERT INTO survey_campaign_state (name) VALUES ('draft');
+INSERT INTO survey_campaign_state (name) VALUES ('open');
+INSERT INTO survey_campaign_state (name) VALUES ('closed');
+
+ALTER TABLE survey_campaigns
+	ADD COLUMN state_id INTEGER references survey_campaign_state(ID),
+	ADD COLUMN starts_at TIMESTAMPTZ DEFAULT NULL,
+	ADD COLUMN ends_at TIMESTAMPTZ DEFAULT NULL,
+	ADD COLUMN max_responses INTEGER DEFAULT NULL;
+
+-- existing campaigns were accepting responses
+UPDATE survey_campaigns
+	SET state_id = (SELECT ID FROM survey_campaign_state WHERE name = 'open');
+
+ALTER TABLE survey_campaigns
+	ALTER COLUMN state_id SET NOT NULL;
@@ -0,0 +1,9 @@
+ALTER TABLE survey_campaigns
+	ADD COLUMN max_responses_per_participant INTEGER DEFAULT NULL;
+
+ALTER TABLE survey_responses
+	ADD COLUMN idempotency_key VARCHAR(100) DEFAULT NULL;
+
+-- NULL keys never conflict, so submissions without a key are unaffected
+CREATE UNIQUE INDEX IF NOT EXISTS survey_responses_idempotency_key
+	ON survey_responses(campaign_id, user_id, idempotency_key);
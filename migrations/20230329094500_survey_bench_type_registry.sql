@@ -0,0 +1,2 @@
+ALTER TABLE survey_bench_type
+	ADD COLUMN retired BOOLEAN NOT NULL DEFAULT FALSE;
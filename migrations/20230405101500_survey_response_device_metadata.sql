@@ -0,0 +1,13 @@
+-- parsed from device_software_recognised at submit time; NULL when unrecognised
+ALTER TABLE survey_responses
+	ADD COLUMN os VARCHAR(400) DEFAULT NULL,
+	ADD COLUMN os_version VARCHAR(400) DEFAULT NULL,
+	ADD COLUMN browser VARCHAR(400) DEFAULT NULL,
+	ADD COLUMN browser_version VARCHAR(400) DEFAULT NULL,
+	ADD COLUMN form_factor VARCHAR(400) DEFAULT NULL;
+
+-- optional hardware hints reported by the browser
+ALTER TABLE survey_responses
+	ADD COLUMN device_memory REAL DEFAULT NULL,
+	ADD COLUMN platform VARCHAR(100) DEFAULT NULL,
+	ADD COLUMN mobile BOOLEAN DEFAULT NULL;
@@ -0,0 +1,7 @@
+-- number of timed samples participants collect for each difficulty
+ALTER TABLE survey_campaigns
+	ADD COLUMN repetitions INTEGER NOT NULL DEFAULT 1;
+
+-- position of a sample among the samples submitted for the same difficulty
+ALTER TABLE survey_benches
+	ADD COLUMN sample INTEGER NOT NULL DEFAULT 0;
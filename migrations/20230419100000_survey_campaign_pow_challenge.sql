@@ -0,0 +1,12 @@
+-- per-campaign PoW challenge that submitted nonces are verified against
+ALTER TABLE survey_campaigns
+	ADD COLUMN pow_salt VARCHAR(100),
+	ADD COLUMN pow_phrase VARCHAR(100);
+
+UPDATE survey_campaigns SET
+	pow_salt = md5(random()::text || ID::text),
+	pow_phrase = md5(random()::text || ID::text);
+
+ALTER TABLE survey_campaigns
+	ALTER COLUMN pow_salt SET NOT NULL,
+	ALTER COLUMN pow_phrase SET NOT NULL;
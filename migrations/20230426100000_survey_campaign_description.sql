@@ -0,0 +1,3 @@
+-- shown to participants on the campaign's about page
+ALTER TABLE survey_campaigns
+	ADD COLUMN description VARCHAR(2000);
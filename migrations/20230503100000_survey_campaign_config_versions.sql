@@ -0,0 +1,20 @@
+-- every change to a campaign's difficulty set creates a new configuration version
+CREATE TABLE IF NOT EXISTS survey_campaign_configs (
+	campaign_id UUID NOT NULL references survey_campaigns(ID) ON DELETE CASCADE,
+	version INTEGER NOT NULL,
+	difficulties INTEGER[] NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL,
+	ID SERIAL PRIMARY KEY NOT NULL,
+	UNIQUE(campaign_id, version)
+);
+
+-- current configuration version of the campaign
+ALTER TABLE survey_campaigns
+	ADD COLUMN config_version INTEGER NOT NULL DEFAULT 1;
+
+-- configuration version that the response was benchmarked against
+ALTER TABLE survey_responses
+	ADD COLUMN config_version INTEGER NOT NULL DEFAULT 1;
+
+INSERT INTO survey_campaign_configs (campaign_id, version, difficulties, created_at)
+	SELECT ID, 1, difficulties, created_at FROM survey_campaigns;
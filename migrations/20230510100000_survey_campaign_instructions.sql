@@ -0,0 +1,3 @@
+-- Markdown instructions shown to participants before they run the benchmark
+ALTER TABLE survey_campaigns
+	ADD COLUMN instructions VARCHAR(2000);
@@ -0,0 +1,11 @@
+-- named campaign settings that admins can pre-fill new campaigns from
+CREATE TABLE IF NOT EXISTS survey_campaign_templates (
+	user_id INTEGER NOT NULL references survey_admins(ID) ON DELETE CASCADE,
+	name VARCHAR(100) NOT NULL,
+	difficulties INTEGER[] NOT NULL,
+	repetitions INTEGER NOT NULL DEFAULT 1,
+	description VARCHAR(2000),
+	instructions VARCHAR(2000),
+	ID SERIAL PRIMARY KEY NOT NULL,
+	UNIQUE(user_id, name)
+);
@@ -0,0 +1,18 @@
+-- admins that a campaign is shared with. The campaign's creator, survey_campaigns.user_id,
+-- is always an owner and isn't listed here.
+CREATE TABLE IF NOT EXISTS survey_campaign_roles (
+	name VARCHAR(30) UNIQUE NOT NULL,
+	ID SERIAL PRIMARY KEY NOT NULL
+);
+
+INSERT INTO survey_campaign_roles (name) VALUES ('owner');
+INSERT INTO survey_campaign_roles (name) VALUES ('editor');
+INSERT INTO survey_campaign_roles (name) VALUES ('viewer');
+
+CREATE TABLE IF NOT EXISTS survey_campaign_members (
+	campaign_id UUID NOT NULL references survey_campaigns(ID) ON DELETE CASCADE,
+	user_id INTEGER NOT NULL references survey_admins(ID) ON DELETE CASCADE,
+	role_id INTEGER NOT NULL references survey_campaign_roles(ID),
+	ID SERIAL PRIMARY KEY NOT NULL,
+	UNIQUE(campaign_id, user_id)
+);
@@ -0,0 +1,3 @@
+-- deleted campaigns stay in the trash, restorable, until their grace period expires
+ALTER TABLE survey_campaigns
+	ADD COLUMN deleted_at TIMESTAMPTZ DEFAULT NULL;
@@ -0,0 +1,5 @@
+-- results are paged by submission time and their benches fetched a page at a time
+CREATE INDEX IF NOT EXISTS survey_responses_campaign_submitted_at
+	ON survey_responses(campaign_id, submitted_at, ID);
+
+CREATE INDEX IF NOT EXISTS survey_benches_resp_id ON survey_benches(resp_id);
@@ -0,0 +1,19 @@
+-- least-squares fit of bench duration (ms) against difficulty factor, see
+-- HashrateFit::fit
+ALTER TABLE survey_responses
+	ADD COLUMN hashrate DOUBLE PRECISION DEFAULT NULL,
+	ADD COLUMN hashrate_r2 DOUBLE PRECISION DEFAULT NULL;
+
+UPDATE survey_responses SET
+	hashrate = fit.hashrate,
+	hashrate_r2 = fit.r2
+FROM (
+	SELECT
+		resp_id,
+		1000.0 / regr_slope(duration, difficulty) AS hashrate,
+		regr_r2(duration, difficulty) AS r2
+	FROM survey_benches
+	GROUP BY resp_id
+	HAVING regr_slope(duration, difficulty) > 0
+) AS fit
+WHERE survey_responses.ID = fit.resp_id;
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS survey_response_review_state (
+	name VARCHAR(30) UNIQUE NOT NULL,
+	ID SERIAL PRIMARY KEY NOT NULL
+);
+
+INSERT INTO survey_response_review_state (name) VALUES ('accepted');
+INSERT INTO survey_response_review_state (name) VALUES ('flagged');
+INSERT INTO survey_response_review_state (name) VALUES ('excluded');
+
+-- flags are the names of the sanity rules that a response broke when it was
+-- received
+ALTER TABLE survey_responses
+	ADD COLUMN review_state_id INTEGER references survey_response_review_state(ID),
+	ADD COLUMN flags TEXT[] NOT NULL DEFAULT '{}',
+	ADD COLUMN reviewed_at TIMESTAMPTZ DEFAULT NULL;
+
+-- responses received before the sanity rules weren't checked
+UPDATE survey_responses
+	SET review_state_id = (
+		SELECT ID FROM survey_response_review_state WHERE name = 'accepted'
+	);
+
+ALTER TABLE survey_responses
+	ALTER COLUMN review_state_id SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS survey_responses_review_state
+	ON survey_responses(campaign_id, review_state_id);
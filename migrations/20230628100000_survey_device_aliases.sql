@@ -0,0 +1,28 @@
+-- match_key is pattern reduced to the lowercase letters and digits that
+-- survey_device_key keeps, with its wildcards turned into LIKE's %
+CREATE TABLE IF NOT EXISTS survey_device_aliases (
+	ID SERIAL PRIMARY KEY NOT NULL,
+	pattern VARCHAR(400) NOT NULL,
+	match_key VARCHAR(400) UNIQUE NOT NULL,
+	canonical VARCHAR(100) NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL
+);
+
+-- device name without case, spaces and punctuation, see device_key
+CREATE OR REPLACE FUNCTION survey_device_key(name TEXT) RETURNS TEXT
+	LANGUAGE SQL IMMUTABLE
+	AS $$ SELECT lower(regexp_replace(name, '[^[:alnum:]]', '', 'g')) $$;
+
+-- canonical name of the alias that matches device name most specifically:
+-- aliases without wildcards first, then the longest pattern
+CREATE OR REPLACE FUNCTION survey_canonical_device(name TEXT) RETURNS TEXT
+	LANGUAGE SQL STABLE
+	AS $$
+		SELECT canonical FROM survey_device_aliases
+		WHERE survey_device_key(name) LIKE match_key
+		ORDER BY
+			position('%' IN match_key) = 0 DESC,
+			length(match_key) DESC,
+			ID
+		LIMIT 1
+	$$;
@@ -0,0 +1,10 @@
+-- survey_canonical_device(device_user_provided), stored when a response is
+-- submitted and recomputed when an alias that matches it is added or removed
+ALTER TABLE survey_responses
+	ADD COLUMN canonical_device VARCHAR(100) DEFAULT NULL;
+
+UPDATE survey_responses SET
+	canonical_device = survey_canonical_device(device_user_provided);
+
+CREATE INDEX IF NOT EXISTS survey_responses_campaign_canonical_device
+	ON survey_responses(campaign_id, canonical_device);
@@ -0,0 +1,25 @@
+-- match_key of alias pattern: the survey_device_key of the text between its
+-- wildcards, joined by LIKE's %
+CREATE OR REPLACE FUNCTION survey_device_match_key(pattern TEXT) RETURNS TEXT
+	LANGUAGE SQL IMMUTABLE
+	AS $$
+		SELECT regexp_replace(
+			array_to_string(
+				ARRAY(
+					SELECT survey_device_key(p.part)
+					FROM unnest(string_to_array(pattern, '*'))
+						WITH ORDINALITY AS p(part, i)
+					ORDER BY p.i
+				),
+				'%'
+			),
+			'%+', '%', 'g'
+		)
+	$$;
+
+UPDATE survey_device_aliases SET match_key = survey_device_match_key(pattern);
+
+UPDATE survey_responses SET
+	canonical_device = survey_canonical_device(device_user_provided)
+WHERE
+	canonical_device IS DISTINCT FROM survey_canonical_device(device_user_provided);
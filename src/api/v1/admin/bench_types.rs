@@ -0,0 +1,400 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Registry of benchmark types that participants can submit, backed by
+//! `survey_bench_type`. Types are never deleted since old submissions refer to
+//! them; retiring a type stops it from accepting new submissions. Only site
+//! admins can change the registry.
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+
+use super::{get_admin_check_login, require_site_admin};
+use crate::api::v1::bench::SubmissionType;
+use crate::errors::*;
+use crate::AppData;
+
+/// length of `survey_bench_type.name`
+pub const MAX_BENCH_TYPE_NAME_LEN: usize = 30;
+
+pub mod routes {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+    pub struct BenchTypes {
+        pub add: &'static str,
+        pub list: &'static str,
+        pub retire: &'static str,
+    }
+
+    impl BenchTypes {
+        pub const fn new() -> BenchTypes {
+            let add = "/admin/api/v1/bench/types/add";
+            let list = "/admin/api/v1/bench/types";
+            let retire = "/admin/api/v1/bench/types/retire";
+            BenchTypes { add, list, retire }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchType {
+    pub name: SubmissionType,
+    pub retired: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchTypePayload {
+    pub name: String,
+}
+
+impl BenchTypePayload {
+    fn validate(&self) -> ServiceResult<SubmissionType> {
+        let valid = !self.name.is_empty()
+            && self.name.len() <= MAX_BENCH_TYPE_NAME_LEN
+            && self.name.chars().all(|c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'
+            });
+        if valid {
+            Ok(SubmissionType::new(self.name.clone()))
+        } else {
+            Err(ServiceError::InvalidBenchTypeName)
+        }
+    }
+}
+
+pub mod runners {
+    use super::*;
+
+    /// all registered benchmark types, including retired ones
+    pub async fn list(data: &AppData) -> ServiceResult<Vec<BenchType>> {
+        struct InnerBenchType {
+            name: String,
+            retired: bool,
+        }
+
+        let mut types = sqlx::query_as!(
+            InnerBenchType,
+            "SELECT name, retired FROM survey_bench_type ORDER BY ID"
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        Ok(types
+            .drain(0..)
+            .map(|t| BenchType {
+                name: SubmissionType::new(t.name),
+                retired: t.retired,
+            })
+            .collect())
+    }
+
+    /// checks if `name` is registered and accepts new submissions
+    pub async fn is_active(
+        name: &SubmissionType,
+        data: &AppData,
+    ) -> ServiceResult<bool> {
+        struct Exists {
+            exists: Option<bool>,
+        }
+
+        let res = sqlx::query_as!(
+            Exists,
+            "SELECT EXISTS (
+                SELECT 1 FROM survey_bench_type
+                WHERE name = $1 AND retired = FALSE
+            );",
+            name.name(),
+        )
+        .fetch_one(&data.db)
+        .await?;
+
+        Ok(res.exists.unwrap_or(false))
+    }
+
+    /// registers `name`; adding a retired type reinstates it
+    pub async fn add(name: &SubmissionType, data: &AppData) -> ServiceResult<()> {
+        let res = sqlx::query!(
+            "INSERT INTO survey_bench_type (name) VALUES ($1)
+            ON CONFLICT (name) DO UPDATE SET retired = FALSE
+            WHERE survey_bench_type.retired = TRUE",
+            name.name(),
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::BenchTypeExists)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub async fn retire(name: &SubmissionType, data: &AppData) -> ServiceResult<()> {
+        let res = sqlx::query!(
+            "UPDATE survey_bench_type SET retired = TRUE WHERE name = $1",
+            name.name(),
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::UnknownBenchType)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(list);
+    cfg.service(add);
+    cfg.service(retire);
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.bench_types.list",
+    wrap = "get_admin_check_login()"
+)]
+async fn list(data: AppData) -> ServiceResult<impl Responder> {
+    let types = runners::list(&data).await?;
+    Ok(HttpResponse::Ok().json(types))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.bench_types.add",
+    wrap = "get_admin_check_login()"
+)]
+async fn add(
+    id: Identity,
+    payload: web::Json<BenchTypePayload>,
+    data: AppData,
+) -> ServiceResult<impl Responder> {
+    require_site_admin(&id.identity().unwrap(), &data)?;
+    let name = payload.validate()?;
+    runners::add(&name, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.bench_types.retire",
+    wrap = "get_admin_check_login()"
+)]
+async fn retire(
+    id: Identity,
+    payload: web::Json<BenchTypePayload>,
+    data: AppData,
+) -> ServiceResult<impl Responder> {
+    require_site_admin(&id.identity().unwrap(), &data)?;
+    let name = payload.validate()?;
+    runners::retire(&name, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use actix_web::ResponseError;
+
+    use super::*;
+    use crate::api::v1::bench::Submission;
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+
+    #[test]
+    fn bench_type_name_validation_works() {
+        let name = |n: &str| BenchTypePayload { name: n.into() }.validate();
+
+        assert_eq!(name("wasm-simd_2"), Ok(SubmissionType::new("wasm-simd_2")));
+        assert_eq!(
+            name(&"a".repeat(MAX_BENCH_TYPE_NAME_LEN)),
+            Ok(SubmissionType::new("a".repeat(MAX_BENCH_TYPE_NAME_LEN)))
+        );
+        for n in [
+            "",
+            "Native",
+            "wasm simd",
+            "wasm.simd",
+            &"a".repeat(MAX_BENCH_TYPE_NAME_LEN + 1),
+        ] {
+            assert_eq!(name(n), Err(ServiceError::InvalidBenchTypeName));
+        }
+    }
+
+    #[actix_rt::test]
+    async fn bench_type_registry_works() {
+        const NAME: &str = "benchtypeuser";
+        const EMAIL: &str = "benchtypeuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+        const BENCH_TYPE: &str = "benchtypeuser-native";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+            sqlx::query!("DELETE FROM survey_bench_type WHERE name = $1", BENCH_TYPE)
+                .execute(&data.db)
+                .await
+                .unwrap();
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let routes = &V1_API_ROUTES.admin.bench_types;
+        let payload = BenchTypePayload {
+            name: BENCH_TYPE.into(),
+        };
+
+        // the registry is shared by all campaigns, so only site admins change it
+        for route in [routes.add, routes.retire] {
+            bad_post_req_test(
+                NAME,
+                PASSWORD,
+                route,
+                &payload,
+                ServiceError::SiteAdminRequired,
+            )
+            .await;
+        }
+        let data = get_site_admin_data(NAME).await;
+        let app = get_app!(data).await;
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&payload, routes.add)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(
+            &app,
+            post_request!(&payload, routes.add)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::BenchTypeExists.status_code());
+
+        let resp = get_request!(&app, routes.list, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let types: Vec<BenchType> = test::read_body_json(resp).await;
+        assert!(types.contains(&BenchType {
+            name: SubmissionType::new(BENCH_TYPE),
+            retired: false
+        }));
+        assert!(types.contains(&BenchType {
+            name: SubmissionType::wasm(),
+            retired: false
+        }));
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "benchtypeuser.v1".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::new(BENCH_TYPE),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(
+            &submit_payload,
+            &campaign,
+            survey_cookie.clone(),
+            data.clone(),
+        )
+        .await;
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&payload, routes.retire)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &submit_payload,
+                &V1_API_ROUTES.benches.submit_route(&campaign.campaign_id)
+            )
+            .cookie(survey_cookie.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::UnknownBenchType.status_code());
+
+        // results recorded against a retired type are still readable
+        let responses = crate::api::v1::admin::campaigns::runners::get_results(
+            NAME,
+            &uuid::Uuid::parse_str(&campaign.campaign_id).unwrap(),
+            &AppData::new(data.clone()),
+            &crate::api::v1::admin::campaigns::ResultsPage {
+                bench_type: Some(SubmissionType::new(BENCH_TYPE)),
+                ..Default::default()
+            },
+            10,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert_eq!(responses.len(), 1);
+        assert_eq!(
+            responses[0].submission_type,
+            SubmissionType::new(BENCH_TYPE)
+        );
+
+        for (name, err) in [
+            ("benchtypeuser-doesntexist", ServiceError::UnknownBenchType),
+            ("Bench Type", ServiceError::InvalidBenchTypeName),
+        ] {
+            let resp = test::call_service(
+                &app,
+                post_request!(&BenchTypePayload { name: name.into() }, routes.retire)
+                    .cookie(cookies.clone())
+                    .to_request(),
+            )
+            .await;
+            assert_eq!(resp.status(), err.status_code());
+        }
+
+        // adding a retired type reinstates it
+        let resp = test::call_service(
+            &app,
+            post_request!(&payload, routes.add)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+    }
+}
@@ -15,16 +15,24 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 use std::borrow::Cow;
+use std::str::FromStr;
 
 use actix_identity::Identity;
 use actix_web::{web, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 use sqlx::types::time::OffsetDateTime;
 use sqlx::types::Uuid;
+use sqlx::{Postgres, Transaction};
 
+use super::members::{self, CampaignRole};
+use super::review::{Review, ReviewState, SanityFlag};
 use super::{get_admin_check_login, get_uuid};
 use crate::api::v1::bench::Bench;
+use crate::api::v1::bench::BenchSummary;
+use crate::api::v1::bench::HashrateFit;
 use crate::api::v1::bench::SubmissionType;
+use crate::api::v1::device::{DeviceHints, UserAgent};
+use crate::api::v1::pow::get_challenge_string;
 use crate::errors::*;
 use crate::AppData;
 
@@ -40,6 +48,11 @@ pub mod routes {
         //    pub get_feedback: &'static str,
         pub list: &'static str,
         pub results: &'static str,
+        pub lifecycle: &'static str,
+        pub update: &'static str,
+        pub clone: &'static str,
+        pub trash: &'static str,
+        pub restore: &'static str,
     }
 
     impl Campaign {
@@ -49,12 +62,22 @@ pub mod routes {
             //            let get_feedback = "/api/v1/campaign/{uuid}/feedback";
             let list = "/admin/api/v1/campaign/list";
             let results = "/admin/api/v1/campaign/{uuid}/results";
+            let lifecycle = "/admin/api/v1/campaign/{uuid}/lifecycle";
+            let update = "/admin/api/v1/campaign/{uuid}/update";
+            let clone = "/admin/api/v1/campaign/{uuid}/clone";
+            let trash = "/admin/api/v1/campaign/trash";
+            let restore = "/admin/api/v1/campaign/{uuid}/restore";
 
             Campaign {
                 add,
                 delete,
                 list,
                 results,
+                lifecycle,
+                update,
+                clone,
+                trash,
+                restore,
             }
         }
         //        pub fn get_benches_route(&self, campaign_id: &str) -> String {
@@ -65,34 +88,38 @@ pub mod routes {
             self.delete.replace("{uuid}", campaign_id)
         }
 
+        pub fn get_lifecycle_route(&self, campaign_id: &str) -> String {
+            self.lifecycle.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_update_route(&self, campaign_id: &str) -> String {
+            self.update.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_clone_route(&self, campaign_id: &str) -> String {
+            self.clone.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_restore_route(&self, campaign_id: &str) -> String {
+            self.restore.replace("{uuid}", campaign_id)
+        }
+
         pub fn get_results_route(
             &self,
             campaign_id: &str,
             modifier: Option<ResultsPage>,
         ) -> String {
-            let mut res = self.results.replace("{uuid}", campaign_id);
-            if let Some(modifier) = modifier {
-                if let Some(page) = modifier.page {
-                    res = format!("{res}?page={page}");
-                }
-
-                if let Some(bench_type) = modifier.bench_type {
-                    if modifier.page.is_some() {
-                        res = format!("{res}&bench_type={}", bench_type.to_string());
-                    } else {
-                        res = format!("{res}?bench_type={}", bench_type.to_string());
-                    }
-                }
+            let res = self.results.replace("{uuid}", campaign_id);
+            match modifier.and_then(|m| m.query_string()) {
+                Some(query) => format!("{res}?{query}"),
+                None => res,
             }
-            res
         }
     }
 }
 
 pub mod runners {
-    use std::str::FromStr;
-
-    use futures::try_join;
+    use std::collections::HashMap;
 
     use crate::api::v1::bench::Bench;
 
@@ -102,28 +129,64 @@ pub mod runners {
         username: &str,
         payload: &mut AddCapmaign,
         data: &AppData,
+    ) -> ServiceResult<sqlx::types::Uuid> {
+        add_with_limits(username, payload, None, None, data).await
+    }
+
+    /// Like [add_runner], for a campaign whose response limits are set when it is
+    /// created, so that it never exists without them
+    async fn add_with_limits(
+        username: &str,
+        payload: &mut AddCapmaign,
+        max_responses: Option<i32>,
+        max_responses_per_participant: Option<i32>,
+        data: &AppData,
     ) -> ServiceResult<sqlx::types::Uuid> {
         let mut uuid;
         let now = OffsetDateTime::now_utc();
 
-        payload.difficulties.sort_unstable();
+        if payload.repetitions < 1 || payload.repetitions > MAX_REPETITIONS {
+            return Err(ServiceError::InvalidRepetitions);
+        }
+        validate_texts(&payload.description, &payload.instructions)?;
+        validate_difficulties(&mut payload.difficulties)?;
+        let salt = get_challenge_string();
+        let phrase = get_challenge_string();
 
         loop {
             uuid = get_uuid();
 
             let res = sqlx::query!(
                 "
-                INSERT INTO survey_campaigns (
-                    user_id, ID, name, difficulties, created_at
-                    ) VALUES(
-                        (SELECT id FROM survey_admins WHERE name = $1),
-                        $2, $3, $4, $5
-                    );",
+                WITH campaign AS (
+                    INSERT INTO survey_campaigns (
+                        user_id, ID, name, difficulties, created_at, state_id,
+                        repetitions, pow_salt, pow_phrase, description, instructions,
+                        max_responses, max_responses_per_participant
+                        ) VALUES(
+                            (SELECT id FROM survey_admins WHERE name = $1),
+                            $2, $3, $4, $5,
+                            (SELECT ID FROM survey_campaign_state WHERE name = $6),
+                            $7, $8, $9, $10, $11, $12, $13
+                        )
+                    RETURNING ID, difficulties, created_at
+                )
+                INSERT INTO survey_campaign_configs
+                    (campaign_id, version, difficulties, created_at)
+                SELECT ID, 1, difficulties, created_at FROM campaign;",
                 username,
                 &uuid,
                 &payload.name,
                 &payload.difficulties,
-                &now
+                &now,
+                CampaignState::Open.to_string(),
+                payload.repetitions,
+                &salt,
+                &phrase,
+                payload.description.as_ref(),
+                payload.instructions.as_ref(),
+                max_responses,
+                max_responses_per_participant,
             )
             .execute(&data.db)
             .await;
@@ -143,6 +206,175 @@ pub mod runners {
         Ok(uuid)
     }
 
+    /// Updates a campaign's name, texts and difficulties. A change to the
+    /// difficulty set creates a new configuration version. Returns the version that is
+    /// current after the update.
+    pub async fn update(
+        username: &str,
+        uuid: &Uuid,
+        payload: &mut UpdateCampaign,
+        data: &AppData,
+    ) -> ServiceResult<i32> {
+        use sqlx::Error::RowNotFound;
+
+        validate_texts(&payload.description, &payload.instructions)?;
+        validate_difficulties(&mut payload.difficulties)?;
+        members::runners::require_role(username, uuid, CampaignRole::Editor, data)
+            .await?;
+
+        let mut tx = data.db.begin().await?;
+
+        struct Current {
+            difficulties: Vec<i32>,
+            config_version: i32,
+        }
+        let res = sqlx::query_as!(
+            Current,
+            "SELECT difficulties, config_version
+            FROM survey_campaigns
+            WHERE ID = $1
+            FOR UPDATE",
+            uuid,
+        )
+        .fetch_one(&mut tx)
+        .await;
+
+        let current = match res {
+            Ok(c) => c,
+            Err(RowNotFound) => return Err(ServiceError::CampaignDoesntExist),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut version = current.config_version;
+        if current.difficulties != payload.difficulties {
+            version += 1;
+            sqlx::query!(
+                "INSERT INTO survey_campaign_configs
+                    (campaign_id, version, difficulties, created_at)
+                VALUES ($1, $2, $3, $4)",
+                uuid,
+                version,
+                &payload.difficulties,
+                OffsetDateTime::now_utc(),
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            "UPDATE survey_campaigns
+            SET
+                name = $1,
+                description = $2,
+                instructions = $3,
+                difficulties = $4,
+                config_version = $5
+            WHERE
+                ID = $6",
+            &payload.name,
+            payload.description.as_ref(),
+            payload.instructions.as_ref(),
+            &payload.difficulties,
+            version,
+            uuid,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(version)
+    }
+
+    /// Creates a new campaign with the configuration and response limits of `uuid`.
+    /// Responses, schedule and the PoW challenge aren't copied. The clone is named
+    /// `name`, or "<original name> (copy)" when it is `None`.
+    pub async fn clone_campaign(
+        username: &str,
+        uuid: &Uuid,
+        name: Option<String>,
+        data: &AppData,
+    ) -> ServiceResult<Uuid> {
+        use sqlx::Error::RowNotFound;
+
+        members::runners::require_role(username, uuid, CampaignRole::Editor, data)
+            .await?;
+
+        struct Source {
+            name: String,
+            difficulties: Vec<i32>,
+            repetitions: i32,
+            description: Option<String>,
+            instructions: Option<String>,
+            max_responses: Option<i32>,
+            max_responses_per_participant: Option<i32>,
+        }
+        let res = sqlx::query_as!(
+            Source,
+            "SELECT
+                name, difficulties, repetitions, description, instructions,
+                max_responses, max_responses_per_participant
+            FROM survey_campaigns
+            WHERE ID = $1",
+            uuid,
+        )
+        .fetch_one(&data.db)
+        .await;
+
+        let source = match res {
+            Ok(s) => s,
+            Err(RowNotFound) => return Err(ServiceError::CampaignDoesntExist),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut payload = AddCapmaign {
+            name: name.unwrap_or_else(|| format!("{} (copy)", source.name)),
+            difficulties: source.difficulties,
+            repetitions: source.repetitions,
+            description: source.description,
+            instructions: source.instructions,
+        };
+        add_with_limits(
+            username,
+            &mut payload,
+            source.max_responses,
+            source.max_responses_per_participant,
+            data,
+        )
+        .await
+    }
+
+    /// all configuration versions of a campaign, oldest first
+    pub async fn get_config_versions(
+        uuid: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<Vec<CampaignConfigVersion>> {
+        struct InnerConfig {
+            version: i32,
+            difficulties: Vec<i32>,
+            created_at: OffsetDateTime,
+        }
+
+        let mut configs = sqlx::query_as!(
+            InnerConfig,
+            "SELECT version, difficulties, created_at
+            FROM survey_campaign_configs
+            WHERE campaign_id = $1
+            ORDER BY version",
+            uuid,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        Ok(configs
+            .drain(0..)
+            .map(|c| CampaignConfigVersion {
+                version: c.version,
+                difficulties: c.difficulties,
+                created_at: c.created_at.unix_timestamp(),
+            })
+            .collect())
+    }
+
     pub async fn list_all_campaigns(
         data: &AppData,
     ) -> ServiceResult<Vec<ListCampaignResp>> {
@@ -153,7 +385,7 @@ pub mod runners {
 
         let mut campaigns = sqlx::query_as!(
             ListCampaign,
-            "SELECT name, id FROM survey_campaigns ORDER BY id;"
+            "SELECT name, id FROM survey_campaigns WHERE deleted_at IS NULL ORDER BY id;"
         )
         .fetch_all(&data.db)
         .await?;
@@ -163,12 +395,14 @@ pub mod runners {
             list_resp.push(ListCampaignResp {
                 name: c.name,
                 uuid: c.id.to_string(),
+                role: None,
             });
         });
 
         Ok(list_resp)
     }
 
+    /// campaigns created by or shared with `username`
     pub async fn list_campaign_runner(
         username: &str,
         data: &AppData,
@@ -176,23 +410,35 @@ pub mod runners {
         struct ListCampaign {
             name: String,
             id: Uuid,
+            role: String,
         }
 
         let mut campaigns = sqlx::query_as!(
             ListCampaign,
-            "SELECT 
-            name, id
-        FROM 
-            survey_campaigns 
+            r#"SELECT
+                survey_campaigns.name,
+                survey_campaigns.ID,
+                CASE
+                    WHEN survey_campaigns.user_id = survey_admins.ID THEN 'owner'
+                    ELSE survey_campaign_roles.name
+                END AS "role!"
+            FROM
+                survey_campaigns
+            INNER JOIN survey_admins ON
+                survey_admins.name = $1
+            LEFT JOIN survey_campaign_members ON
+                survey_campaign_members.campaign_id = survey_campaigns.ID
+            AND
+                survey_campaign_members.user_id = survey_admins.ID
+            LEFT JOIN survey_campaign_roles ON
+                survey_campaign_roles.ID = survey_campaign_members.role_id
             WHERE
-                user_id = (
-                    SELECT 
-                        ID
-                    FROM 
-                        survey_admins
-                    WHERE
-                        name = $1
-                )",
+                survey_campaigns.deleted_at IS NULL
+            AND (
+                survey_campaigns.user_id = survey_admins.ID
+            OR
+                survey_campaign_members.ID IS NOT NULL
+            )"#,
             username
         )
         .fetch_all(&data.db)
@@ -203,6 +449,7 @@ pub mod runners {
             list_resp.push(ListCampaignResp {
                 name: c.name,
                 uuid: c.id.to_string(),
+                role: Some(CampaignRole::from_str(&c.role).unwrap()),
             });
         });
 
@@ -217,167 +464,218 @@ pub mod runners {
         threads: Option<i32>,
         device_user_provided: String,
         device_software_recognised: String,
+        canonical_device: Option<String>,
         name: String,
+        os: Option<String>,
+        os_version: Option<String>,
+        browser: Option<String>,
+        browser_version: Option<String>,
+        form_factor: Option<String>,
+        device_memory: Option<f32>,
+        platform: Option<String>,
+        mobile: Option<bool>,
+        config_version: i32,
+        hashrate: Option<f64>,
+        hashrate_r2: Option<f64>,
+        review_state: String,
+        flags: Vec<String>,
+        reviewed_at: Option<OffsetDateTime>,
+        user_created_at: OffsetDateTime,
     }
 
     #[derive(Debug)]
-    struct InnerU {
-        created_at: OffsetDateTime,
-        id: Uuid,
-    }
-
-    impl From<InnerU> for SurveyUser {
-        fn from(u: InnerU) -> Self {
-            Self {
-                id: uuid::Uuid::parse_str(&u.id.to_string()).unwrap(),
-                created_at: u.created_at.unix_timestamp(),
-            }
-        }
+    struct InternalResponseBench {
+        resp_id: i32,
+        duration: f32,
+        difficulty: i32,
     }
 
+    /// a page of `uuid`'s responses that match `query`, in submission order
     pub async fn get_results(
         username: &str,
         uuid: &Uuid,
         data: &AppData,
-        page: usize,
+        query: &ResultsPage,
         limit: usize,
-        filter: Option<SubmissionType>,
-    ) -> ServiceResult<Vec<SurveyResponse>> {
-        let mut db_responses = if let Some(filter) = filter {
-            sqlx::query_as!(
-                InternalSurveyResp,
-                "SELECT
-                    survey_responses.ID,
-                    survey_responses.device_software_recognised,
-                    survey_responses.threads,
-                    survey_responses.user_id,
-                    survey_responses.submitted_at,
-                    survey_responses.device_user_provided,
-                    survey_bench_type.name
-                FROM
-                    survey_responses
-                INNER JOIN  survey_bench_type ON
-                    survey_responses.submission_bench_type_id = survey_bench_type.ID
-                WHERE
-                    survey_bench_type.name = $3
-                AND
-                    survey_responses.campaign_id = (
-                        SELECT ID FROM survey_campaigns
-                        WHERE
-                            ID = $1
-                        AND
-                            user_id = (SELECT ID FROM survey_admins WHERE name = $2)
-                    )
-                LIMIT $4 OFFSET $5",
-                uuid,
-                username,
-                filter.to_string(),
-                limit as i32,
-                page as i32,
-            )
-            .fetch_all(&data.db)
-            .await?
-        } else {
-            #[derive(Debug)]
-            struct I {
-                id: i32,
-                submitted_at: OffsetDateTime,
-                user_id: Uuid,
-                threads: Option<i32>,
-                device_user_provided: String,
-                device_software_recognised: String,
-                name: String,
-            }
-            let mut i = sqlx::query_as!(
-                I,
-                "SELECT
+    ) -> ServiceResult<SurveyResults> {
+        members::runners::require_role(username, uuid, CampaignRole::Viewer, data)
+            .await?;
+
+        let filter = query.parse()?;
+        let (after_submitted_at, after_id) = match filter.after {
+            Some(c) => (Some(c.submitted_at), Some(c.id)),
+            None => (None, None),
+        };
+
+        // one extra row tells us if there's a next page
+        let mut db_responses = sqlx::query_as!(
+            InternalSurveyResp,
+            "SELECT
                 survey_responses.ID,
                 survey_responses.device_software_recognised,
                 survey_responses.threads,
                 survey_responses.user_id,
                 survey_responses.submitted_at,
                 survey_responses.device_user_provided,
-                survey_bench_type.name
+                survey_responses.canonical_device,
+                survey_bench_type.name,
+                survey_responses.os,
+                survey_responses.os_version,
+                survey_responses.browser,
+                survey_responses.browser_version,
+                survey_responses.form_factor,
+                survey_responses.device_memory,
+                survey_responses.platform,
+                survey_responses.mobile,
+                survey_responses.config_version,
+                survey_responses.hashrate,
+                survey_responses.hashrate_r2,
+                survey_response_review_state.name AS review_state,
+                survey_responses.flags,
+                survey_responses.reviewed_at,
+                survey_users.created_at AS user_created_at
             FROM
                 survey_responses
             INNER JOIN  survey_bench_type ON
                 survey_responses.submission_bench_type_id = survey_bench_type.ID
+            INNER JOIN survey_response_review_state ON
+                survey_responses.review_state_id = survey_response_review_state.ID
+            INNER JOIN survey_users ON
+                survey_users.ID = survey_responses.user_id
             WHERE
-                survey_responses.campaign_id = (
-                    SELECT ID FROM survey_campaigns
-                    WHERE
-                        ID = $1
-                    AND
-                        user_id = (SELECT ID FROM survey_admins WHERE name = $2)
-                )
-            LIMIT $3 OFFSET $4",
-                uuid,
-                username,
-                limit as i32,
-                page as i32,
+                survey_responses.campaign_id = $1
+            AND
+                ($2::TEXT IS NULL OR survey_bench_type.name = $2)
+            AND
+                ($3::TIMESTAMPTZ IS NULL OR survey_responses.submitted_at >= $3)
+            AND
+                ($4::TIMESTAMPTZ IS NULL OR survey_responses.submitted_at < $4)
+            AND
+                ($5::INTEGER IS NULL OR survey_responses.threads >= $5)
+            AND
+                ($6::INTEGER IS NULL OR survey_responses.threads <= $6)
+            AND (
+                $7::TEXT IS NULL
+            OR
+                survey_responses.device_user_provided ILIKE $7
+            OR
+                survey_responses.device_software_recognised ILIKE $7
+            OR
+                survey_responses.canonical_device ILIKE $7
             )
-            .fetch_all(&data.db)
-            .await?;
+            AND (
+                $8::TIMESTAMPTZ IS NULL
+            OR
+                (survey_responses.submitted_at, survey_responses.ID) > ($8, $9)
+            )
+            AND
+                ($11::TEXT IS NULL OR survey_response_review_state.name = $11)
+            ORDER BY
+                survey_responses.submitted_at, survey_responses.ID
+            LIMIT $10",
+            uuid,
+            query.bench_type.as_ref().map(|t| t.to_string()),
+            filter.from,
+            filter.to,
+            query.min_threads,
+            query.max_threads,
+            filter.device,
+            after_submitted_at,
+            after_id,
+            limit as i64 + 1,
+            query.review.as_ref().map(|r| r.to_string()),
+        )
+        .fetch_all(&data.db)
+        .await?;
 
-            let mut res = Vec::with_capacity(i.len());
-            i.drain(0..).for_each(|x| {
-                res.push(InternalSurveyResp {
-                    id: x.id,
-                    submitted_at: x.submitted_at,
-                    user_id: x.user_id,
-                    threads: x.threads,
-                    device_user_provided: x.device_user_provided,
-                    device_software_recognised: x.device_software_recognised,
-                    name: x.name,
-                })
-            });
-            res
+        let next = if db_responses.len() > limit {
+            db_responses.truncate(limit);
+            db_responses.last().map(|r| {
+                ResultsCursor {
+                    submitted_at: r.submitted_at,
+                    id: r.id,
+                }
+                .to_string()
+            })
+        } else {
+            None
         };
 
+        // benches of the whole page at once, grouped by response below
+        let resp_ids: Vec<i32> = db_responses.iter().map(|r| r.id).collect();
+        let db_benches = sqlx::query_as!(
+            InternalResponseBench,
+            "SELECT
+                resp_id,
+                duration,
+                difficulty
+            FROM
+                survey_benches
+            WHERE
+                resp_id = ANY($1)
+            ORDER BY
+                resp_id, difficulty, sample
+           ",
+            &resp_ids,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        let mut page_benches: HashMap<i32, Vec<Bench>> = HashMap::new();
+        for b in db_benches {
+            page_benches.entry(b.resp_id).or_default().push(Bench {
+                duration: b.duration,
+                difficulty: b.difficulty,
+            });
+        }
+
         let mut responses = Vec::with_capacity(db_responses.len());
         for r in db_responses.drain(0..) {
-            let benches_fut = sqlx::query_as!(
-                Bench,
-                "SELECT
-                    duration,
-                    difficulty
-                FROM
-                    survey_benches
-                WHERE
-                    resp_id = $1
-               ",
-                r.id,
-            )
-            .fetch_all(&data.db);
-
-            let user_fut = sqlx::query_as!(
-                InnerU,
-                "SELECT
-                    created_at,
-                    ID
-                FROM
-                    survey_users
-                WHERE
-                    ID = $1
-               ",
-                r.user_id,
-            )
-            .fetch_one(&data.db);
-
-            let (benches, user) = try_join!(benches_fut, user_fut)?;
-            let user = user.into();
+            let benches = page_benches.remove(&r.id).unwrap_or_default();
+            let user = SurveyUser {
+                id: uuid::Uuid::parse_str(&r.user_id.to_string()).unwrap(),
+                created_at: r.user_created_at.unix_timestamp(),
+            };
             responses.push(SurveyResponse {
+                summary: BenchSummary::summarize(&benches),
                 benches,
                 user,
                 device_user_provided: r.device_user_provided,
                 device_software_recognised: r.device_software_recognised,
+                canonical_device: r.canonical_device,
                 submitted_at: r.submitted_at.unix_timestamp(),
                 id: r.id as usize,
-                submission_type: SubmissionType::from_str(&r.name).unwrap(),
+                submission_type: SubmissionType::new(r.name),
                 threads: r.threads.map(|t| t as usize),
+                config_version: r.config_version,
+                hashrate: r
+                    .hashrate
+                    .zip(r.hashrate_r2)
+                    .map(|(hashrate, r2)| HashrateFit { hashrate, r2 }),
+                review: Review {
+                    state: ReviewState::from_str(&r.review_state).unwrap(),
+                    flags: r
+                        .flags
+                        .iter()
+                        .map(|f| SanityFlag::from_str(f).unwrap())
+                        .collect(),
+                    reviewed_at: r.reviewed_at.map(|t| t.unix_timestamp()),
+                },
+                user_agent: UserAgent {
+                    os: r.os,
+                    os_version: r.os_version,
+                    browser: r.browser,
+                    browser_version: r.browser_version,
+                    form_factor: r.form_factor,
+                },
+                hints: DeviceHints {
+                    memory: r.device_memory,
+                    platform: r.platform,
+                    mobile: r.mobile,
+                },
             })
         }
-        Ok(responses)
+        Ok(SurveyResults { responses, next })
     }
 
     pub async fn delete(
@@ -385,27 +683,322 @@ pub mod runners {
         username: &str,
         data: &AppData,
     ) -> ServiceResult<()> {
+        members::runners::require_role(username, uuid, CampaignRole::Owner, data)
+            .await?;
         sqlx::query!(
-            "DELETE 
-            FROM survey_campaigns 
-         WHERE 
-             user_id = (
-                 SELECT 
-                     ID 
-                 FROM 
-                     survey_admins 
-                 WHERE 
-                     name = $1
-             )
-         AND
-            id = ($2)",
-            username,
-            uuid
+            "UPDATE survey_campaigns SET deleted_at = $1 WHERE id = $2",
+            OffsetDateTime::now_utc(),
+            uuid,
         )
         .execute(&data.db)
         .await?;
         Ok(())
     }
+
+    /// trashed campaigns that `username` owns, most recently deleted first
+    pub async fn list_trash(
+        username: &str,
+        data: &AppData,
+    ) -> ServiceResult<Vec<TrashedCampaign>> {
+        struct InnerTrashed {
+            name: String,
+            id: Uuid,
+            deleted_at: OffsetDateTime,
+        }
+
+        let mut campaigns = sqlx::query_as!(
+            InnerTrashed,
+            r#"SELECT name, ID, deleted_at AS "deleted_at!"
+            FROM survey_campaigns
+            WHERE
+                deleted_at IS NOT NULL
+            AND (
+                user_id = (SELECT ID FROM survey_admins WHERE name = $1)
+            OR
+                ID IN (
+                    SELECT survey_campaign_members.campaign_id
+                    FROM survey_campaign_members
+                    INNER JOIN survey_admins ON
+                        survey_admins.ID = survey_campaign_members.user_id
+                    INNER JOIN survey_campaign_roles ON
+                        survey_campaign_roles.ID = survey_campaign_members.role_id
+                    WHERE
+                        survey_admins.name = $1
+                    AND
+                        survey_campaign_roles.name = 'owner'
+                )
+            )
+            ORDER BY deleted_at DESC"#,
+            username,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        let grace_period = data.settings.trash.grace_period as i64;
+        Ok(campaigns
+            .drain(0..)
+            .map(|c| {
+                let deleted_at = c.deleted_at.unix_timestamp();
+                TrashedCampaign {
+                    name: c.name,
+                    uuid: c.id.to_string(),
+                    deleted_at,
+                    purge_at: deleted_at + grace_period,
+                }
+            })
+            .collect())
+    }
+
+    /// takes a campaign that `username` owns out of the trash
+    pub async fn restore(
+        username: &str,
+        uuid: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        let res = sqlx::query!(
+            "UPDATE survey_campaigns
+            SET deleted_at = NULL
+            WHERE
+                ID = $1
+            AND
+                deleted_at IS NOT NULL
+            AND (
+                user_id = (SELECT ID FROM survey_admins WHERE name = $2)
+            OR
+                ID IN (
+                    SELECT survey_campaign_members.campaign_id
+                    FROM survey_campaign_members
+                    INNER JOIN survey_admins ON
+                        survey_admins.ID = survey_campaign_members.user_id
+                    INNER JOIN survey_campaign_roles ON
+                        survey_campaign_roles.ID = survey_campaign_members.role_id
+                    WHERE
+                        survey_admins.name = $2
+                    AND
+                        survey_campaign_roles.name = 'owner'
+                )
+            )",
+            uuid,
+            username,
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::CampaignDoesntExist)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Permanently deletes campaigns, along with their responses, that have been in
+    /// the trash for longer than the grace period. Returns the number of campaigns
+    /// that were deleted.
+    pub async fn purge_trash(data: &AppData) -> ServiceResult<u64> {
+        let cutoff = OffsetDateTime::now_utc()
+            - std::time::Duration::from_secs(data.settings.trash.grace_period);
+        let res = sqlx::query!(
+            "DELETE FROM survey_campaigns WHERE deleted_at <= $1",
+            cutoff,
+        )
+        .execute(&data.db)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    /// Errors when the campaign doesn't exist or isn't accepting responses at the moment.
+    /// Per-participant limits are checked when `participant` is set.
+    pub async fn check_accepting_responses(
+        uuid: &Uuid,
+        participant: Option<&Uuid>,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        use sqlx::Error::RowNotFound;
+
+        struct InnerLifecycle {
+            name: String,
+            starts_at: Option<OffsetDateTime>,
+            ends_at: Option<OffsetDateTime>,
+            max_responses: Option<i32>,
+            max_responses_per_participant: Option<i32>,
+            responses: i64,
+            participant_responses: i64,
+        }
+
+        let res = sqlx::query_as!(
+            InnerLifecycle,
+            r#"SELECT
+                survey_campaign_state.name,
+                survey_campaigns.starts_at,
+                survey_campaigns.ends_at,
+                survey_campaigns.max_responses,
+                survey_campaigns.max_responses_per_participant,
+                (
+                    SELECT COUNT(*) FROM survey_responses
+                    WHERE campaign_id = $1
+                ) AS "responses!",
+                (
+                    SELECT COUNT(*) FROM survey_responses
+                    WHERE campaign_id = $1 AND user_id = $2
+                ) AS "participant_responses!"
+            FROM
+                survey_campaigns
+            INNER JOIN survey_campaign_state ON
+                survey_campaigns.state_id = survey_campaign_state.ID
+            WHERE
+                survey_campaigns.ID = $1
+            AND
+                survey_campaigns.deleted_at IS NULL"#,
+            uuid,
+            participant,
+        )
+        .fetch_one(&data.db)
+        .await;
+
+        let l = match res {
+            Ok(l) => l,
+            Err(RowNotFound) => return Err(ServiceError::CampaignDoesntExist),
+            Err(e) => return Err(e.into()),
+        };
+
+        let lifecycle = CampaignLifecycle {
+            state: CampaignState::from_str(&l.name).unwrap(),
+            starts_at: l.starts_at.map(|t| t.unix_timestamp()),
+            ends_at: l.ends_at.map(|t| t.unix_timestamp()),
+            max_responses: l.max_responses,
+            max_responses_per_participant: l.max_responses_per_participant,
+        };
+        lifecycle.accepts_responses(
+            OffsetDateTime::now_utc().unix_timestamp(),
+            l.responses,
+            l.participant_responses,
+        )
+    }
+
+    /// Like [check_accepting_responses], for a submission that is about to be written
+    /// in `tx`. The campaign stays locked until `tx` ends, so that concurrent
+    /// submissions and lifecycle changes can't slip past the check.
+    pub async fn lock_accepting_responses(
+        uuid: &Uuid,
+        participant: &Uuid,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> ServiceResult<()> {
+        use sqlx::Error::RowNotFound;
+
+        struct InnerLifecycle {
+            name: String,
+            starts_at: Option<OffsetDateTime>,
+            ends_at: Option<OffsetDateTime>,
+            max_responses: Option<i32>,
+            max_responses_per_participant: Option<i32>,
+        }
+
+        let res = sqlx::query_as!(
+            InnerLifecycle,
+            "SELECT
+                survey_campaign_state.name,
+                survey_campaigns.starts_at,
+                survey_campaigns.ends_at,
+                survey_campaigns.max_responses,
+                survey_campaigns.max_responses_per_participant
+            FROM
+                survey_campaigns
+            INNER JOIN survey_campaign_state ON
+                survey_campaigns.state_id = survey_campaign_state.ID
+            WHERE
+                survey_campaigns.ID = $1
+            AND
+                survey_campaigns.deleted_at IS NULL
+            FOR UPDATE OF survey_campaigns",
+            uuid,
+        )
+        .fetch_one(&mut *tx)
+        .await;
+
+        let l = match res {
+            Ok(l) => l,
+            Err(RowNotFound) => return Err(ServiceError::CampaignDoesntExist),
+            Err(e) => return Err(e.into()),
+        };
+
+        // counted in a statement of its own: the one above may have waited for the
+        // lock and its snapshot misses responses that were committed in the meantime
+        let counts = sqlx::query!(
+            r#"SELECT
+                COUNT(*) AS "responses!",
+                COUNT(*) FILTER (WHERE user_id = $2) AS "participant_responses!"
+            FROM
+                survey_responses
+            WHERE
+                campaign_id = $1"#,
+            uuid,
+            participant,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let lifecycle = CampaignLifecycle {
+            state: CampaignState::from_str(&l.name).unwrap(),
+            starts_at: l.starts_at.map(|t| t.unix_timestamp()),
+            ends_at: l.ends_at.map(|t| t.unix_timestamp()),
+            max_responses: l.max_responses,
+            max_responses_per_participant: l.max_responses_per_participant,
+        };
+        lifecycle.accepts_responses(
+            OffsetDateTime::now_utc().unix_timestamp(),
+            counts.responses,
+            counts.participant_responses,
+        )
+    }
+
+    pub async fn update_lifecycle(
+        username: &str,
+        uuid: &Uuid,
+        payload: &CampaignLifecycle,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        payload.validate()?;
+
+        let to_time = |t: Option<i64>| -> ServiceResult<Option<OffsetDateTime>> {
+            match t {
+                Some(t) => Ok(Some(
+                    OffsetDateTime::from_unix_timestamp(t)
+                        .map_err(|_| ServiceError::InvalidCampaignSchedule)?,
+                )),
+                None => Ok(None),
+            }
+        };
+        let starts_at = to_time(payload.starts_at)?;
+        let ends_at = to_time(payload.ends_at)?;
+        members::runners::require_role(username, uuid, CampaignRole::Editor, data)
+            .await?;
+
+        let res = sqlx::query!(
+            "UPDATE survey_campaigns
+            SET
+                state_id = (SELECT ID FROM survey_campaign_state WHERE name = $1),
+                starts_at = $2,
+                ends_at = $3,
+                max_responses = $4,
+                max_responses_per_participant = $5
+            WHERE
+                ID = $6",
+            payload.state.to_string(),
+            starts_at,
+            ends_at,
+            payload.max_responses,
+            payload.max_responses_per_participant,
+            uuid,
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::CampaignDoesntExist)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 #[actix_web_codegen_const_routes::post(
@@ -424,48 +1017,305 @@ pub async fn delete(
     Ok(HttpResponse::Ok())
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct SurveyResponse {
-    pub user: SurveyUser,
-    pub device_user_provided: String,
-    pub device_software_recognised: String,
-    pub id: usize,
-    pub threads: Option<usize>,
-    pub submitted_at: i64,
-    pub submission_type: SubmissionType,
-    pub benches: Vec<Bench>,
-}
-
+/// campaign that was deleted and can be restored until `purge_at`
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct SurveyUser {
-    pub created_at: i64, // OffsetDateTime,
-    pub id: uuid::Uuid,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ListCampaignResp {
+pub struct TrashedCampaign {
     pub name: String,
     pub uuid: String,
+    pub deleted_at: i64,
+    pub purge_at: i64,
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.campaign.trash",
+    wrap = "get_admin_check_login()"
+)]
+pub async fn list_trash(id: Identity, data: AppData) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let trash = runners::list_trash(&username, &data).await?;
+    Ok(HttpResponse::Ok().json(trash))
 }
 
 #[actix_web_codegen_const_routes::post(
-    path = "crate::V1_API_ROUTES.admin.campaign.list",
+    path = "crate::V1_API_ROUTES.admin.campaign.restore",
     wrap = "get_admin_check_login()"
 )]
-pub async fn list_campaign(
+pub async fn restore(
     id: Identity,
     data: AppData,
+    path: web::Path<String>,
 ) -> ServiceResult<impl Responder> {
     let username = id.identity().unwrap();
-    let list_resp = runners::list_campaign_runner(&username, &data).await?;
+    let path = path.into_inner();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    runners::restore(&username, &uuid, &data).await?;
+    Ok(HttpResponse::Ok())
+}
 
-    Ok(HttpResponse::Ok().json(list_resp))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CampaignState {
+    Draft,
+    Open,
+    Closed,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct AddCapmaign {
+impl ToString for CampaignState {
+    fn to_string(&self) -> String {
+        let s = serde_json::to_string(&self).unwrap();
+        (&s[1..(s.len() - 1)]).to_string()
+    }
+}
+
+impl FromStr for CampaignState {
+    type Err = serde_json::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(&format!("\"{}\"", s))
+    }
+}
+
+/// Whether a campaign accepts responses, as shown to participants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignStatus {
+    NotStarted,
+    Open,
+    Closed,
+}
+
+/// Controls when a campaign accepts responses. Timestamps are UNIX timestamps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignLifecycle {
+    pub state: CampaignState,
+    pub starts_at: Option<i64>,
+    pub ends_at: Option<i64>,
+    pub max_responses: Option<i32>,
+    /// `None` allows unlimited responses from a participant
+    pub max_responses_per_participant: Option<i32>,
+}
+
+impl CampaignLifecycle {
+    pub fn validate(&self) -> ServiceResult<()> {
+        if let (Some(starts_at), Some(ends_at)) = (self.starts_at, self.ends_at) {
+            if ends_at <= starts_at {
+                return Err(ServiceError::InvalidCampaignSchedule);
+            }
+        }
+        let limits = [self.max_responses, self.max_responses_per_participant];
+        if limits.iter().flatten().any(|limit| *limit < 1) {
+            return Err(ServiceError::InvalidResponseLimit);
+        }
+        Ok(())
+    }
+
+    /// check if campaign accepts responses at time `now`, given that it already has
+    /// `responses` responses, `participant_responses` of which are from the submitting
+    /// participant
+    pub fn accepts_responses(
+        &self,
+        now: i64,
+        responses: i64,
+        participant_responses: i64,
+    ) -> ServiceResult<()> {
+        match self.state {
+            CampaignState::Draft => return Err(ServiceError::CampaignNotStarted),
+            CampaignState::Closed => return Err(ServiceError::CampaignClosed),
+            CampaignState::Open => (),
+        }
+
+        if let Some(starts_at) = self.starts_at {
+            if now < starts_at {
+                return Err(ServiceError::CampaignNotStarted);
+            }
+        }
+        if let Some(ends_at) = self.ends_at {
+            if now >= ends_at {
+                return Err(ServiceError::CampaignClosed);
+            }
+        }
+        if let Some(max_responses) = self.max_responses {
+            if responses >= max_responses as i64 {
+                return Err(ServiceError::CampaignClosed);
+            }
+        }
+        if let Some(max_responses) = self.max_responses_per_participant {
+            if participant_responses >= max_responses as i64 {
+                return Err(ServiceError::SubmissionLimitReached);
+            }
+        }
+        Ok(())
+    }
+
+    /// status of the campaign at time `now`, ignoring per-participant limits
+    pub fn status(&self, now: i64, responses: i64) -> CampaignStatus {
+        match self.accepts_responses(now, responses, 0) {
+            Ok(()) => CampaignStatus::Open,
+            Err(ServiceError::CampaignNotStarted) => CampaignStatus::NotStarted,
+            Err(_) => CampaignStatus::Closed,
+        }
+    }
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.campaign.lifecycle",
+    wrap = "get_admin_check_login()"
+)]
+pub async fn update_lifecycle(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    payload: web::Json<CampaignLifecycle>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let path = path.into_inner();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    runners::update_lifecycle(&username, &uuid, &payload, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.campaign.update",
+    wrap = "get_admin_check_login()"
+)]
+pub async fn update(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    payload: web::Json<UpdateCampaign>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let path = path.into_inner();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    let mut payload = payload.into_inner();
+    let config_version = runners::update(&username, &uuid, &mut payload, &data).await?;
+    Ok(HttpResponse::Ok().json(UpdateCampaignResp { config_version }))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.campaign.clone",
+    wrap = "get_admin_check_login()"
+)]
+pub async fn clone_campaign(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    payload: web::Json<CloneCampaign>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let path = path.into_inner();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    let name = payload.into_inner().name;
+    let campaign_id = runners::clone_campaign(&username, &uuid, name, &data).await?;
+    Ok(HttpResponse::Ok().json(AddCapmaignResp {
+        campaign_id: campaign_id.to_string(),
+    }))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurveyResponse {
+    pub user: SurveyUser,
+    pub device_user_provided: String,
+    pub device_software_recognised: String,
+    /// name of the device alias that matches `device_user_provided`
+    pub canonical_device: Option<String>,
+    pub id: usize,
+    pub threads: Option<usize>,
+    pub submitted_at: i64,
+    pub submission_type: SubmissionType,
+    /// version of the campaign configuration that the response was benchmarked against
+    pub config_version: i32,
+    /// `None` when the benches don't span enough difficulties to fit
+    pub hashrate: Option<HashrateFit>,
+    pub review: Review,
+    pub user_agent: UserAgent,
+    pub hints: DeviceHints,
+    pub benches: Vec<Bench>,
+    pub summary: Vec<BenchSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SurveyUser {
+    pub created_at: i64, // OffsetDateTime,
+    pub id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListCampaignResp {
+    pub name: String,
+    pub uuid: String,
+    /// role of the admin that the campaigns were listed for, `None` in public listings
+    pub role: Option<CampaignRole>,
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.campaign.list",
+    wrap = "get_admin_check_login()"
+)]
+pub async fn list_campaign(
+    id: Identity,
+    data: AppData,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let list_resp = runners::list_campaign_runner(&username, &data).await?;
+
+    Ok(HttpResponse::Ok().json(list_resp))
+}
+
+/// samples collected for each difficulty when a campaign doesn't specify it
+pub const DEFAULT_REPETITIONS: i32 = 1;
+/// upper bound on [AddCapmaign::repetitions]
+pub const MAX_REPETITIONS: i32 = 10;
+/// length of `survey_campaigns.description`
+pub const MAX_DESCRIPTION_LEN: usize = 2000;
+/// length of `survey_campaigns.instructions`
+pub const MAX_INSTRUCTIONS_LEN: usize = 2000;
+
+pub fn validate_texts(
+    description: &Option<String>,
+    instructions: &Option<String>,
+) -> ServiceResult<()> {
+    let too_long = |text: &Option<String>, max| {
+        text.as_ref().map_or(false, |t| t.chars().count() > max)
+    };
+    if too_long(description, MAX_DESCRIPTION_LEN) {
+        return Err(ServiceError::CampaignDescriptionTooLong);
+    }
+    if too_long(instructions, MAX_INSTRUCTIONS_LEN) {
+        return Err(ServiceError::CampaignInstructionsTooLong);
+    }
+    Ok(())
+}
+
+/// Sorts `difficulties` and checks that the campaign has at least one, and that
+/// they are distinct and positive
+pub fn validate_difficulties(difficulties: &mut [i32]) -> ServiceResult<()> {
+    difficulties.sort_unstable();
+    let valid = difficulties.first().map_or(false, |d| *d > 0)
+        && difficulties.windows(2).all(|w| w[0] != w[1]);
+    if valid {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidDifficulties)
+    }
+}
+
+pub fn default_repetitions() -> i32 {
+    DEFAULT_REPETITIONS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddCapmaign {
     pub name: String,
     pub difficulties: Vec<i32>,
+    /// number of samples participants collect for each difficulty
+    #[serde(default = "default_repetitions")]
+    pub repetitions: i32,
+    /// Markdown, shown to participants on the campaign's about page
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Markdown, shown to participants before they run the benchmark
+    #[serde(default)]
+    pub instructions: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -473,29 +1323,212 @@ pub struct AddCapmaignResp {
     pub campaign_id: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCampaign {
+    pub name: String,
+    pub difficulties: Vec<i32>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloneCampaign {
+    /// defaults to the source campaign's name, suffixed with " (copy)"
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCampaignResp {
+    pub config_version: i32,
+}
+
+/// Difficulty set that a campaign used, starting from `created_at` until the next
+/// version was created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignConfigVersion {
+    pub version: i32,
+    pub difficulties: Vec<i32>,
+    pub created_at: i64,
+}
+
 pub fn services(cfg: &mut web::ServiceConfig) {
     cfg.service(add);
     cfg.service(delete);
     cfg.service(list_campaign);
     cfg.service(get_campaign_resutls);
+    cfg.service(update_lifecycle);
+    cfg.service(update);
+    cfg.service(clone_campaign);
+    cfg.service(list_trash);
+    cfg.service(restore);
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+/// filters and cursor for a page of campaign results
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct ResultsPage {
-    page: Option<usize>,
+    /// cursor of the previous page, see [SurveyResults::next]
+    pub after: Option<String>,
     pub bench_type: Option<SubmissionType>,
+    /// only responses submitted at or after this UNIX timestamp
+    pub from: Option<i64>,
+    /// only responses submitted before this UNIX timestamp
+    pub to: Option<i64>,
+    pub min_threads: Option<i32>,
+    pub max_threads: Option<i32>,
+    /// case-insensitive search in user provided, detected and canonical device names
+    pub device: Option<String>,
+    pub review: Option<ReviewState>,
+}
+
+/// [ResultsPage] with its values checked and converted for the database
+struct ResultsFilter {
+    after: Option<ResultsCursor>,
+    from: Option<OffsetDateTime>,
+    to: Option<OffsetDateTime>,
+    /// `ILIKE` pattern
+    device: Option<String>,
 }
 
 impl ResultsPage {
-    pub fn page(&self) -> usize {
-        self.page.unwrap_or(0)
+    /// same filters, starting after `cursor`
+    pub fn with_after(&self, cursor: Option<String>) -> Self {
+        let mut page = self.clone();
+        page.after = cursor;
+        page
+    }
+
+    /// same page, filtered by `bench_type`
+    pub fn with_bench_type(&self, bench_type: Option<SubmissionType>) -> Self {
+        let mut page = self.with_after(None);
+        page.bench_type = bench_type;
+        page
+    }
+
+    /// URL query string, `None` when no filter is set
+    pub fn query_string(&self) -> Option<String> {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if let Some(after) = &self.after {
+            query.append_pair("after", after);
+        }
+        if let Some(bench_type) = &self.bench_type {
+            query.append_pair("bench_type", bench_type.name());
+        }
+        if let Some(from) = self.from {
+            query.append_pair("from", &from.to_string());
+        }
+        if let Some(to) = self.to {
+            query.append_pair("to", &to.to_string());
+        }
+        if let Some(min_threads) = self.min_threads {
+            query.append_pair("min_threads", &min_threads.to_string());
+        }
+        if let Some(max_threads) = self.max_threads {
+            query.append_pair("max_threads", &max_threads.to_string());
+        }
+        if let Some(device) = &self.device {
+            query.append_pair("device", device);
+        }
+        if let Some(review) = &self.review {
+            query.append_pair("review", &review.to_string());
+        }
+        let query = query.finish();
+        if query.is_empty() {
+            None
+        } else {
+            Some(query)
+        }
+    }
+
+    fn parse(&self) -> ServiceResult<ResultsFilter> {
+        let timestamp = |t: Option<i64>| {
+            t.map(OffsetDateTime::from_unix_timestamp)
+                .transpose()
+                .map_err(|_| ServiceError::InvalidResultsFilter)
+        };
+        let from = timestamp(self.from)?;
+        let to = timestamp(self.to)?;
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(ServiceError::InvalidResultsFilter);
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_threads, self.max_threads) {
+            if min > max {
+                return Err(ServiceError::InvalidResultsFilter);
+            }
+        }
+
+        let after = self
+            .after
+            .as_deref()
+            .map(ResultsCursor::from_str)
+            .transpose()?;
+
+        let device = self
+            .device
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|d| {
+                let d = d
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_");
+                format!("%{d}%")
+            });
+
+        Ok(ResultsFilter {
+            after,
+            from,
+            to,
+            device,
+        })
+    }
+}
+
+/// position of a response in the results, ordered by submission time and ID
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct ResultsCursor {
+    submitted_at: OffsetDateTime,
+    id: i32,
+}
+
+impl ToString for ResultsCursor {
+    fn to_string(&self) -> String {
+        // Postgres stores timestamps with microsecond precision
+        let micros = self.submitted_at.unix_timestamp_nanos() / 1000;
+        format!("{micros}_{}", self.id)
     }
+}
 
-    pub fn new(page: Option<usize>, bench_type: Option<SubmissionType>) -> Self {
-        Self { page, bench_type }
+impl FromStr for ResultsCursor {
+    type Err = ServiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (micros, id) = s
+            .split_once('_')
+            .ok_or(ServiceError::InvalidResultsCursor)?;
+        let micros: i64 = micros
+            .parse()
+            .map_err(|_| ServiceError::InvalidResultsCursor)?;
+        let id = id.parse().map_err(|_| ServiceError::InvalidResultsCursor)?;
+        let nanos = i128::from(micros) * 1000;
+        let submitted_at = OffsetDateTime::from_unix_timestamp_nanos(nanos)
+            .map_err(|_| ServiceError::InvalidResultsCursor)?;
+        Ok(Self { submitted_at, id })
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurveyResults {
+    pub responses: Vec<SurveyResponse>,
+    /// cursor of the next page, `None` on the last page
+    pub next: Option<String>,
+}
+
 #[actix_web_codegen_const_routes::get(
     path = "crate::V1_API_ROUTES.admin.campaign.results",
     wrap = "get_admin_check_login()"
@@ -507,13 +1540,9 @@ pub async fn get_campaign_resutls(
     data: AppData,
 ) -> ServiceResult<impl Responder> {
     let username = id.identity().unwrap();
-    let query = query.into_inner();
-    let page = query.page();
     let path = Uuid::parse_str(&path.to_string()).unwrap();
 
-    let results =
-        runners::get_results(&username, &path, &data, page, 50, query.bench_type)
-            .await?;
+    let results = runners::get_results(&username, &path, &data, &query, 50).await?;
 
     Ok(HttpResponse::Ok().json(results))
 }
@@ -537,6 +1566,7 @@ async fn add(
 mod tests {
     use crate::api::v1::bench::Submission;
     use crate::api::v1::bench::SubmissionType;
+    use crate::api::v1::device::{DeviceHints, UserAgent};
     use crate::errors::*;
     use crate::tests::*;
     use crate::*;
@@ -607,8 +1637,20 @@ mod tests {
             device_user_provided: DEVICE_USER_PROVIDED.into(),
             device_software_recognised: DEVICE_SOFTWARE_RECOGNISED.into(),
             threads: THREADS,
-            benches: BENCHES.clone(),
-            submission_type: SubmissionType::Wasm,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints {
+                memory: Some(8.0),
+                platform: None,
+                mobile: Some(true),
+            },
         };
 
         let _proof =
@@ -621,14 +1663,19 @@ mod tests {
             NAME,
             &sqlx::types::Uuid::parse_str(&campaign.campaign_id).unwrap(),
             &AppData::new(data.clone()),
-            0,
+            &super::ResultsPage::default(),
             50,
-            None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .responses;
         assert_eq!(responses.len(), 1);
         assert_eq!(responses[0].threads, Some(THREADS as usize));
+        assert_eq!(
+            responses[0].user_agent,
+            UserAgent::parse(DEVICE_SOFTWARE_RECOGNISED)
+        );
+        assert_eq!(responses[0].hints, submit_payload.hints);
         let mut l = responses[0].benches.clone();
         l.sort_by(|a, b| a.difficulty.cmp(&b.difficulty));
         let mut r = BENCHES.clone();
@@ -639,12 +1686,15 @@ mod tests {
                 NAME,
                 &sqlx::types::Uuid::parse_str(&campaign.campaign_id).unwrap(),
                 &AppData::new(data.clone()),
-                0,
+                &super::ResultsPage {
+                    bench_type: Some(SubmissionType::wasm()),
+                    ..Default::default()
+                },
                 50,
-                Some(SubmissionType::Wasm),
             )
             .await
-            .unwrap(),
+            .unwrap()
+            .responses,
             responses
         );
 
@@ -653,12 +1703,15 @@ mod tests {
                 NAME,
                 &sqlx::types::Uuid::parse_str(&campaign.campaign_id).unwrap(),
                 &AppData::new(data.clone()),
-                0,
+                &super::ResultsPage {
+                    bench_type: Some(SubmissionType::js()),
+                    ..Default::default()
+                },
                 50,
-                Some(SubmissionType::Js),
             )
             .await
-            .unwrap(),
+            .unwrap()
+            .responses,
             Vec::default()
         );
 
@@ -668,6 +1721,9 @@ mod tests {
             DEVICE_SOFTWARE_RECOGNISED
         );
         assert_eq!(responses[0].device_user_provided, DEVICE_USER_PROVIDED);
+        // BENCHES take a millisecond per difficulty factor
+        let fit = responses[0].hashrate.as_ref().unwrap();
+        assert!((fit.hashrate - 1000.0).abs() < 1e-6);
 
         let results_resp = get_request!(
             &app,
@@ -678,8 +1734,9 @@ mod tests {
             cookies.clone()
         );
         assert_eq!(results_resp.status(), StatusCode::OK);
-        let res: Vec<super::SurveyResponse> = test::read_body_json(results_resp).await;
-        assert_eq!(responses, res);
+        let res: super::SurveyResults = test::read_body_json(results_resp).await;
+        assert_eq!(responses, res.responses);
+        assert!(res.next.is_none());
 
         bad_post_req_test_witout_payload(
             NAME,
@@ -694,4 +1751,978 @@ mod tests {
         let list = list_campaings(data.clone(), cookies.clone()).await;
         assert!(!list.iter().any(|c| c.name == NAME));
     }
+
+    #[test]
+    fn campaign_lifecycle_works() {
+        use super::{CampaignLifecycle, CampaignState, CampaignStatus};
+
+        const NOW: i64 = 1_000;
+
+        let mut l = CampaignLifecycle {
+            state: CampaignState::Open,
+            starts_at: None,
+            ends_at: None,
+            max_responses: None,
+            max_responses_per_participant: None,
+        };
+        assert!(l.validate().is_ok());
+        assert!(l.accepts_responses(NOW, 100, 0).is_ok());
+
+        assert_eq!(l.status(NOW, 100), CampaignStatus::Open);
+
+        l.state = CampaignState::Draft;
+        assert_eq!(
+            l.accepts_responses(NOW, 0, 0),
+            Err(ServiceError::CampaignNotStarted)
+        );
+        assert_eq!(l.status(NOW, 0), CampaignStatus::NotStarted);
+        l.state = CampaignState::Closed;
+        assert_eq!(
+            l.accepts_responses(NOW, 0, 0),
+            Err(ServiceError::CampaignClosed)
+        );
+        assert_eq!(l.status(NOW, 0), CampaignStatus::Closed);
+        l.state = CampaignState::Open;
+
+        l.starts_at = Some(NOW + 1);
+        assert_eq!(
+            l.accepts_responses(NOW, 0, 0),
+            Err(ServiceError::CampaignNotStarted)
+        );
+        l.starts_at = Some(NOW - 1);
+        assert!(l.accepts_responses(NOW, 0, 0).is_ok());
+
+        l.ends_at = Some(NOW);
+        assert_eq!(
+            l.accepts_responses(NOW, 0, 0),
+            Err(ServiceError::CampaignClosed)
+        );
+        l.ends_at = Some(NOW - 1);
+        assert_eq!(l.validate(), Err(ServiceError::InvalidCampaignSchedule));
+        l.ends_at = None;
+
+        l.max_responses = Some(2);
+        assert!(l.accepts_responses(NOW, 1, 0).is_ok());
+        assert_eq!(
+            l.accepts_responses(NOW, 2, 0),
+            Err(ServiceError::CampaignClosed)
+        );
+        l.max_responses = Some(0);
+        assert_eq!(l.validate(), Err(ServiceError::InvalidResponseLimit));
+        l.max_responses = None;
+
+        l.max_responses_per_participant = Some(1);
+        assert!(l.validate().is_ok());
+        assert!(l.accepts_responses(NOW, 10, 0).is_ok());
+        assert_eq!(l.status(NOW, 10), CampaignStatus::Open);
+        assert_eq!(
+            l.accepts_responses(NOW, 10, 1),
+            Err(ServiceError::SubmissionLimitReached)
+        );
+        l.max_responses_per_participant = Some(0);
+        assert_eq!(l.validate(), Err(ServiceError::InvalidResponseLimit));
+    }
+
+    #[actix_rt::test]
+    async fn update_lifecycle_works() {
+        use actix_web::ResponseError;
+
+        use super::{CampaignLifecycle, CampaignState};
+
+        const NAME: &str = "lifecycleuser";
+        const EMAIL: &str = "lifecycleuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let lifecycle_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_lifecycle_route(&campaign.campaign_id);
+        let fetch_route = V1_API_ROUTES.benches.fetch_routes(&campaign.campaign_id);
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "lifecycleuser.v1".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+
+        let mut lifecycle = CampaignLifecycle {
+            state: CampaignState::Closed,
+            starts_at: None,
+            ends_at: None,
+            max_responses: None,
+            max_responses_per_participant: None,
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&lifecycle, &lifecycle_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = get_request!(&app, &fetch_route, survey_cookie.clone());
+        assert_eq!(resp.status(), ServiceError::CampaignClosed.status_code());
+        let resp = get_request!(
+            &app,
+            &PAGES.panel.campaigns.get_about_route(&campaign.campaign_id)
+        );
+        assert_eq!(resp.status(), ServiceError::CampaignClosed.status_code());
+
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &submit_payload,
+                &V1_API_ROUTES.benches.submit_route(&campaign.campaign_id)
+            )
+            .cookie(survey_cookie.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::CampaignClosed.status_code());
+
+        lifecycle.state = CampaignState::Open;
+        let resp = test::call_service(
+            &app,
+            post_request!(&lifecycle, &lifecycle_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = get_request!(&app, &fetch_route, survey_cookie.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        lifecycle.max_responses = Some(0);
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &lifecycle_route,
+            &lifecycle,
+            ServiceError::InvalidResponseLimit,
+        )
+        .await;
+    }
+
+    #[actix_rt::test]
+    async fn campaign_repetitions_work() {
+        use super::{AddCapmaign, AddCapmaignResp, MAX_REPETITIONS};
+        use crate::api::v1::bench::{Bench, BenchSummary};
+
+        const NAME: &str = "repetitionsuser";
+        const EMAIL: &str = "repetitionsuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let mut add = AddCapmaign {
+            name: NAME.into(),
+            difficulties: vec![1, 2],
+            repetitions: 3,
+            description: None,
+            instructions: None,
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, V1_API_ROUTES.admin.campaign.add)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let campaign: AddCapmaignResp = test::read_body_json(resp).await;
+
+        let config =
+            get_campaign_config(&campaign, data.clone(), survey_cookie.clone()).await;
+        assert_eq!(config.repetitions, 3);
+
+        let bench = |difficulty, duration| Bench {
+            difficulty,
+            duration,
+        };
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "repetitionsuser.v1".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &[bench(1, 3.0), bench(2, 5.0), bench(1, 1.0), bench(1, 8.0)],
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        let responses = super::runners::get_results(
+            NAME,
+            &sqlx::types::Uuid::parse_str(&campaign.campaign_id).unwrap(),
+            &AppData::new(data.clone()),
+            &super::ResultsPage::default(),
+            50,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert_eq!(responses.len(), 1);
+        assert_eq!(
+            responses[0].summary,
+            vec![
+                BenchSummary {
+                    difficulty: 1,
+                    samples: vec![3.0, 1.0, 8.0],
+                    min: 1.0,
+                    median: 3.0,
+                },
+                BenchSummary {
+                    difficulty: 2,
+                    samples: vec![5.0],
+                    min: 5.0,
+                    median: 5.0,
+                },
+            ]
+        );
+
+        for repetitions in [0, MAX_REPETITIONS + 1] {
+            add.repetitions = repetitions;
+            bad_post_req_test(
+                NAME,
+                PASSWORD,
+                V1_API_ROUTES.admin.campaign.add,
+                &add,
+                ServiceError::InvalidRepetitions,
+            )
+            .await;
+        }
+        add.repetitions = 1;
+        add.difficulties = vec![1, 1];
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.campaign.add,
+            &add,
+            ServiceError::InvalidDifficulties,
+        )
+        .await;
+    }
+
+    #[actix_rt::test]
+    async fn update_campaign_works() {
+        use super::{
+            UpdateCampaign, UpdateCampaignResp, MAX_DESCRIPTION_LEN,
+            MAX_INSTRUCTIONS_LEN,
+        };
+        use crate::api::v1::bench::Bench;
+
+        const NAME: &str = "updatecampaignuser";
+        const EMAIL: &str = "updatecampaignuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+        const NEW_NAME: &str = "updatecampaignuser-renamed";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let campaign_id = sqlx::types::Uuid::parse_str(&campaign.campaign_id).unwrap();
+        let update_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_update_route(&campaign.campaign_id);
+
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "updatecampaignuser.v1".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(
+            &submit_payload,
+            &campaign,
+            survey_cookie.clone(),
+            data.clone(),
+        )
+        .await;
+
+        let update = |difficulties: Vec<i32>| UpdateCampaign {
+            name: NEW_NAME.into(),
+            difficulties,
+            description: Some("foo".into()),
+            instructions: None,
+        };
+        // renaming or reordering difficulties doesn't create a new version
+        let mut difficulties = DIFFICULTIES.to_vec();
+        difficulties.reverse();
+        let resp = test::call_service(
+            &app,
+            post_request!(&update(difficulties), &update_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp: UpdateCampaignResp = test::read_body_json(resp).await;
+        assert_eq!(resp.config_version, 1);
+        let campaigns = list_campaings(data.clone(), cookies.clone()).await;
+        assert!(campaigns
+            .iter()
+            .any(|c| c.uuid == campaign.campaign_id && c.name == NEW_NAME));
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&update(vec![6, 1]), &update_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp: UpdateCampaignResp = test::read_body_json(resp).await;
+        assert_eq!(resp.config_version, 2);
+
+        let config =
+            get_campaign_config(&campaign, data.clone(), survey_cookie.clone()).await;
+        assert_eq!(config.difficulties, vec![1, 6]);
+        assert_eq!(config.version, 2);
+
+        let benches = [
+            Bench {
+                difficulty: 1,
+                duration: 1.0,
+            },
+            Bench {
+                difficulty: 6,
+                duration: 6.0,
+            },
+        ];
+        let submit_payload = Submission {
+            device_software_recognised: "updatecampaignuser.v2".into(),
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &benches,
+            )
+            .await,
+            ..submit_payload
+        };
+        submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        let data = AppData::new(data.clone());
+        let mut responses = super::runners::get_results(
+            NAME,
+            &campaign_id,
+            &data,
+            &Default::default(),
+            10,
+        )
+        .await
+        .unwrap()
+        .responses;
+        responses.sort_by_key(|r| r.id);
+        let versions: Vec<i32> = responses.iter().map(|r| r.config_version).collect();
+        assert_eq!(versions, vec![1, 2]);
+        assert_eq!(responses[1].benches, benches.to_vec());
+
+        let configs = super::runners::get_config_versions(&campaign_id, &data)
+            .await
+            .unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].version, 1);
+        assert_eq!(configs[0].difficulties, DIFFICULTIES.to_vec());
+        assert_eq!(configs[1].version, 2);
+        assert_eq!(configs[1].difficulties, vec![1, 6]);
+
+        let mut payload = update(vec![1]);
+        payload.description = Some("a".repeat(MAX_DESCRIPTION_LEN + 1));
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &update_route,
+            &payload,
+            ServiceError::CampaignDescriptionTooLong,
+        )
+        .await;
+        let mut payload = update(vec![1]);
+        payload.instructions = Some("a".repeat(MAX_INSTRUCTIONS_LEN + 1));
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &update_route,
+            &payload,
+            ServiceError::CampaignInstructionsTooLong,
+        )
+        .await;
+        // bad difficulties are rejected before a new configuration is written
+        for difficulties in [vec![], vec![0, 1], vec![-1, 1], vec![2, 2]] {
+            bad_post_req_test(
+                NAME,
+                PASSWORD,
+                &update_route,
+                &update(difficulties),
+                ServiceError::InvalidDifficulties,
+            )
+            .await;
+        }
+        let configs = super::runners::get_config_versions(&campaign_id, &data)
+            .await
+            .unwrap();
+        assert_eq!(configs.len(), 2);
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &V1_API_ROUTES
+                .admin
+                .campaign
+                .get_update_route(&crate::api::v1::get_uuid().to_string()),
+            &update(vec![1]),
+            ServiceError::CampaignDoesntExist,
+        )
+        .await;
+    }
+
+    #[actix_rt::test]
+    async fn clone_campaign_works() {
+        use actix_web::ResponseError;
+
+        use super::{AddCapmaignResp, CampaignLifecycle, CampaignState, CloneCampaign};
+
+        const NAME: &str = "clonecampaignuser";
+        const EMAIL: &str = "clonecampaignuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let lifecycle = CampaignLifecycle {
+            state: CampaignState::Open,
+            starts_at: None,
+            ends_at: None,
+            max_responses: Some(1),
+            max_responses_per_participant: Some(3),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &lifecycle,
+                &V1_API_ROUTES
+                    .admin
+                    .campaign
+                    .get_lifecycle_route(&campaign.campaign_id)
+            )
+            .cookie(cookies.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "clonecampaignuser.v1".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(
+            &submit_payload,
+            &campaign,
+            survey_cookie.clone(),
+            data.clone(),
+        )
+        .await;
+
+        let clone_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_clone_route(&campaign.campaign_id);
+        let resp = test::call_service(
+            &app,
+            post_request!(&CloneCampaign { name: None }, &clone_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let clone: AddCapmaignResp = test::read_body_json(resp).await;
+        assert_ne!(clone.campaign_id, campaign.campaign_id);
+        let limits = sqlx::query!(
+            "SELECT max_responses, max_responses_per_participant
+            FROM survey_campaigns WHERE ID = $1",
+            &sqlx::types::Uuid::parse_str(&clone.campaign_id).unwrap(),
+        )
+        .fetch_one(&data.db)
+        .await
+        .unwrap();
+        assert_eq!(limits.max_responses, Some(1));
+        assert_eq!(limits.max_responses_per_participant, Some(3));
+
+        let list = list_campaings(data.clone(), cookies.clone()).await;
+        let copy_name = format!("{NAME} (copy)");
+        assert!(list
+            .iter()
+            .any(|c| c.uuid == clone.campaign_id && c.name == copy_name));
+
+        // same configuration, fresh challenge and no responses
+        let config =
+            get_campaign_config(&campaign, data.clone(), survey_cookie.clone()).await;
+        let clone_config =
+            get_campaign_config(&clone, data.clone(), survey_cookie.clone()).await;
+        assert_eq!(clone_config.difficulties, config.difficulties);
+        assert_eq!(clone_config.repetitions, config.repetitions);
+        assert_eq!(clone_config.version, 1);
+        assert_ne!(clone_config.salt, config.salt);
+        let responses = super::runners::get_results(
+            NAME,
+            &sqlx::types::Uuid::parse_str(&clone.campaign_id).unwrap(),
+            &AppData::new(data.clone()),
+            &super::ResultsPage::default(),
+            50,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert!(responses.is_empty());
+
+        // response limit is carried over
+        let submit_payload = Submission {
+            device_software_recognised: "clonecampaignuser.v2".into(),
+            benches: solve_benches(
+                &clone,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            ..submit_payload
+        };
+        submit_bench(&submit_payload, &clone, survey_cookie.clone(), data.clone()).await;
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES.benches.fetch_routes(&clone.campaign_id),
+            survey_cookie.clone()
+        );
+        assert_eq!(resp.status(), ServiceError::CampaignClosed.status_code());
+
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &CloneCampaign {
+                    name: Some("renamed".into())
+                },
+                &clone_route
+            )
+            .cookie(cookies.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let list = list_campaings(data.clone(), cookies.clone()).await;
+        assert!(list.iter().any(|c| c.name == "renamed"));
+
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &V1_API_ROUTES
+                .admin
+                .campaign
+                .get_clone_route(&crate::api::v1::get_uuid().to_string()),
+            &CloneCampaign { name: None },
+            ServiceError::CampaignDoesntExist,
+        )
+        .await;
+    }
+
+    #[actix_rt::test]
+    async fn trash_and_restore_work() {
+        use std::time::Duration;
+
+        use sqlx::types::time::OffsetDateTime;
+
+        use super::TrashedCampaign;
+
+        const NAME: &str = "trashcampaignuser";
+        const EMAIL: &str = "trashcampaignuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let campaign_id = sqlx::types::Uuid::parse_str(&campaign.campaign_id).unwrap();
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "trashcampaignuser.v1".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(
+            &submit_payload,
+            &campaign,
+            survey_cookie.clone(),
+            data.clone(),
+        )
+        .await;
+
+        let results_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_results_route(&campaign.campaign_id, None);
+        let restore_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_restore_route(&campaign.campaign_id);
+
+        delete_campaign(&campaign, data.clone(), cookies.clone()).await;
+
+        // trashed campaigns are hidden from admins and participants
+        let list = list_campaings(data.clone(), cookies.clone()).await;
+        assert!(!list.iter().any(|c| c.uuid == campaign.campaign_id));
+        let resp = get_request!(&app, &results_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES.benches.fetch_routes(&campaign.campaign_id),
+            survey_cookie.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp =
+            get_request!(&app, V1_API_ROUTES.admin.campaign.trash, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let trash: Vec<TrashedCampaign> = test::read_body_json(resp).await;
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].uuid, campaign.campaign_id);
+        assert_eq!(
+            trash[0].purge_at - trash[0].deleted_at,
+            data.settings.trash.grace_period as i64
+        );
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&restore_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let list = list_campaings(data.clone(), cookies.clone()).await;
+        assert!(list.iter().any(|c| c.uuid == campaign.campaign_id));
+        let appdata = AppData::new(data.clone());
+        let responses = super::runners::get_results(
+            NAME,
+            &campaign_id,
+            &appdata,
+            &Default::default(),
+            50,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert_eq!(responses.len(), 1);
+
+        // campaigns are only purged once their grace period expires
+        delete_campaign(&campaign, data.clone(), cookies.clone()).await;
+        super::runners::purge_trash(&appdata).await.unwrap();
+        assert_eq!(
+            super::runners::list_trash(NAME, &appdata)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let expired = OffsetDateTime::now_utc()
+            - Duration::from_secs(data.settings.trash.grace_period + 1);
+        sqlx::query!(
+            "UPDATE survey_campaigns SET deleted_at = $1 WHERE ID = $2",
+            expired,
+            &campaign_id,
+        )
+        .execute(&data.db)
+        .await
+        .unwrap();
+        assert!(super::runners::purge_trash(&appdata).await.unwrap() >= 1);
+        assert!(super::runners::list_trash(NAME, &appdata)
+            .await
+            .unwrap()
+            .is_empty());
+
+        bad_post_req_test_witout_payload(
+            NAME,
+            PASSWORD,
+            &restore_route,
+            ServiceError::CampaignDoesntExist,
+        )
+        .await;
+    }
+
+    #[actix_rt::test]
+    async fn results_pagination_works() {
+        use actix_web::ResponseError;
+        use sqlx::types::time::OffsetDateTime;
+
+        use super::{ResultsCursor, ResultsPage, SurveyResults};
+
+        const NAME: &str = "resultspaginationuser";
+        const EMAIL: &str = "resultspaginationuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+        const DEVICES: [(&str, i32); 3] =
+            [("Pixel 7", 2), ("ThinkPad X1", 4), ("iPhone_12", 8)];
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let campaign_id = sqlx::types::Uuid::parse_str(&campaign.campaign_id).unwrap();
+        for (device, threads) in DEVICES {
+            let submit_payload = Submission {
+                device_user_provided: device.into(),
+                device_software_recognised: "resultspaginationuser".into(),
+                threads,
+                benches: solve_benches(
+                    &campaign,
+                    data.clone(),
+                    survey_cookie.clone(),
+                    &BENCHES,
+                )
+                .await,
+                submission_type: SubmissionType::wasm(),
+                idempotency_key: None,
+                hints: DeviceHints::default(),
+            };
+            submit_bench(
+                &submit_payload,
+                &campaign,
+                survey_cookie.clone(),
+                data.clone(),
+            )
+            .await;
+        }
+
+        let appdata = AppData::new(data.clone());
+        let get = |query: ResultsPage, limit: usize| {
+            let appdata = appdata.clone();
+            async move {
+                super::runners::get_results(NAME, &campaign_id, &appdata, &query, limit)
+                    .await
+            }
+        };
+        let devices = |results: &SurveyResults| -> Vec<String> {
+            results
+                .responses
+                .iter()
+                .map(|r| r.device_user_provided.clone())
+                .collect()
+        };
+
+        // pages are ordered by submission and don't skip or repeat responses
+        let first = get(ResultsPage::default(), 2).await.unwrap();
+        assert_eq!(devices(&first), vec!["Pixel 7", "ThinkPad X1"]);
+        assert!(first.next.is_some());
+        let second = get(ResultsPage::default().with_after(first.next), 2)
+            .await
+            .unwrap();
+        assert_eq!(devices(&second), vec!["iPhone_12"]);
+        assert!(second.next.is_none());
+        let all = get(ResultsPage::default(), 3).await.unwrap();
+        assert_eq!(all.responses.len(), 3);
+        assert!(all.next.is_none());
+
+        let threads = |min_threads, max_threads| ResultsPage {
+            min_threads,
+            max_threads,
+            ..Default::default()
+        };
+        let res = get(threads(Some(4), None), 10).await.unwrap();
+        assert_eq!(devices(&res), vec!["ThinkPad X1", "iPhone_12"]);
+        let res = get(threads(Some(4), Some(4)), 10).await.unwrap();
+        assert_eq!(devices(&res), vec!["ThinkPad X1"]);
+
+        let device = |device: &str| ResultsPage {
+            device: Some(device.into()),
+            ..Default::default()
+        };
+        let res = get(device("pixel"), 10).await.unwrap();
+        assert_eq!(devices(&res), vec!["Pixel 7"]);
+        // LIKE wildcards are matched literally
+        let res = get(device("_"), 10).await.unwrap();
+        assert_eq!(devices(&res), vec!["iPhone_12"]);
+        let res = get(device("%"), 10).await.unwrap();
+        assert!(res.responses.is_empty());
+
+        let later = OffsetDateTime::now_utc().unix_timestamp() + 3600;
+        let res = get(
+            ResultsPage {
+                from: Some(later),
+                ..Default::default()
+            },
+            10,
+        )
+        .await
+        .unwrap();
+        assert!(res.responses.is_empty());
+        let res = get(
+            ResultsPage {
+                to: Some(later),
+                ..Default::default()
+            },
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.responses.len(), 3);
+
+        let res = get(
+            ResultsPage {
+                bench_type: Some(SubmissionType::js()),
+                ..Default::default()
+            },
+            10,
+        )
+        .await
+        .unwrap();
+        assert!(res.responses.is_empty());
+
+        assert_eq!(
+            get(threads(Some(8), Some(2)), 10).await,
+            Err(ServiceError::InvalidResultsFilter)
+        );
+        assert_eq!(
+            get(ResultsPage::default().with_after(Some("foo".into())), 10).await,
+            Err(ServiceError::InvalidResultsCursor)
+        );
+        // timestamps too large for a date are rejected rather than overflowing
+        for cursor in [
+            "999999999999999999999999999999999999999_1".to_string(),
+            "170141183460469231731687303715884106_1".to_string(),
+            format!("{}_1", i64::MAX),
+            format!("{}_1", i64::MIN),
+        ] {
+            assert_eq!(
+                cursor.parse::<ResultsCursor>(),
+                Err(ServiceError::InvalidResultsCursor)
+            );
+        }
+
+        // filters are passed through the API's query string
+        let route = V1_API_ROUTES.admin.campaign.get_results_route(
+            &campaign.campaign_id,
+            Some(ResultsPage {
+                device: Some("thinkpad x1".into()),
+                min_threads: Some(2),
+                ..Default::default()
+            }),
+        );
+        let resp = get_request!(&app, &route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let res: SurveyResults = test::read_body_json(resp).await;
+        assert_eq!(devices(&res), vec!["ThinkPad X1"]);
+
+        for cursor in ["1_foo", "170141183460469231731687303715884106_1"] {
+            let route = V1_API_ROUTES.admin.campaign.get_results_route(
+                &campaign.campaign_id,
+                Some(ResultsPage::default().with_after(Some(cursor.into()))),
+            );
+            let resp = get_request!(&app, &route, cookies.clone());
+            assert_eq!(
+                resp.status(),
+                ServiceError::InvalidResultsCursor.status_code()
+            );
+        }
+    }
 }
@@ -0,0 +1,682 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Aliases that map the free-text device names that participants provide to
+//! canonical device names, backed by `survey_device_aliases`. Names are compared
+//! by their `survey_device_key`, so aliases ignore case, spaces and punctuation.
+//! Aliases apply to all campaigns, so only site admins can change them.
+use std::collections::HashSet;
+
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{Postgres, Transaction};
+
+use super::{get_admin_check_login, require_site_admin};
+use crate::api::v1::bench::MAX_DEVICE_INFO_LEN;
+use crate::errors::*;
+use crate::AppData;
+
+/// length of `survey_device_aliases.canonical`
+pub const MAX_CANONICAL_DEVICE_LEN: usize = 100;
+/// trigram similarity from which device names are suggested as one device
+pub const SUGGESTION_SIMILARITY: f64 = 0.5;
+/// device keys at least this long are suggested as one device with the keys that
+/// contain them
+pub const MIN_CONTAINED_KEY_LEN: usize = 4;
+
+pub mod routes {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+    pub struct Devices {
+        pub list: &'static str,
+        pub add: &'static str,
+        pub remove: &'static str,
+        pub suggestions: &'static str,
+    }
+
+    impl Devices {
+        pub const fn new() -> Devices {
+            let list = "/admin/api/v1/devices/aliases";
+            let add = "/admin/api/v1/devices/aliases/add";
+            let remove = "/admin/api/v1/devices/aliases/remove";
+            let suggestions = "/admin/api/v1/devices/suggestions";
+            Devices {
+                list,
+                add,
+                remove,
+                suggestions,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceAlias {
+    pub id: i32,
+    /// device name, where `*` matches any run of characters
+    pub pattern: String,
+    pub canonical: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddDeviceAlias {
+    pub pattern: String,
+    pub canonical: String,
+}
+
+impl AddDeviceAlias {
+    /// `match_key` is the pattern's, see [runners::match_key]; a pattern must match
+    /// more than any device name
+    fn validate(&self, match_key: &str) -> ServiceResult<()> {
+        let canonical = self.canonical.trim();
+        if self.pattern.chars().count() > MAX_DEVICE_INFO_LEN
+            || !match_key.chars().any(|c| c != '%')
+            || canonical.is_empty()
+            || canonical.chars().count() > MAX_CANONICAL_DEVICE_LEN
+        {
+            Err(ServiceError::InvalidDeviceAlias)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoveDeviceAlias {
+    pub id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceCount {
+    /// as provided by participants
+    pub name: String,
+    pub responses: i64,
+}
+
+/// device names that are probably the same device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceGroup {
+    /// most common name in the group
+    pub canonical: String,
+    /// ordered by number of responses, most common first
+    pub devices: Vec<DeviceCount>,
+}
+
+fn trigrams(key: &str) -> HashSet<[char; 3]> {
+    let padded: Vec<char> = format!("  {key} ").chars().collect();
+    padded.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// share of trigrams, between 0 and 1, that device keys `a` and `b` have in common
+fn similarity(a: &str, b: &str) -> f64 {
+    let a = trigrams(a);
+    let b = trigrams(b);
+    let common = a.intersection(&b).count();
+    common as f64 / (a.len() + b.len() - common) as f64
+}
+
+/// Groups device names that are probably the same device. `devices` pairs each
+/// name's `survey_device_key` with its count and must be ordered by number of
+/// responses, most common first; each group is led by its most common name and
+/// only names that have a letter or a digit are grouped.
+/// Names that aren't similar to any other name are left out.
+pub fn suggest_groups(devices: Vec<(String, DeviceCount)>) -> Vec<DeviceGroup> {
+    let mut groups: Vec<(String, DeviceGroup)> = Vec::new();
+    for (key, device) in devices {
+        if key.is_empty() {
+            continue;
+        }
+
+        let group = groups.iter_mut().find(|(group_key, _)| {
+            let (short, long) = if group_key.len() < key.len() {
+                (group_key.as_str(), key.as_str())
+            } else {
+                (key.as_str(), group_key.as_str())
+            };
+            (short.len() >= MIN_CONTAINED_KEY_LEN && long.contains(short))
+                || similarity(group_key, &key) >= SUGGESTION_SIMILARITY
+        });
+        match group {
+            Some((_, group)) => group.devices.push(device),
+            None => groups.push((
+                key,
+                DeviceGroup {
+                    canonical: device.name.clone(),
+                    devices: vec![device],
+                },
+            )),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(_, group)| group)
+        .filter(|group| group.devices.len() > 1)
+        .collect()
+}
+
+pub mod runners {
+    use super::*;
+
+    /// all aliases, ordered by canonical name and pattern
+    pub async fn list(data: &AppData) -> ServiceResult<Vec<DeviceAlias>> {
+        let aliases = sqlx::query_as!(
+            DeviceAlias,
+            "SELECT ID AS id, pattern, canonical
+            FROM survey_device_aliases
+            ORDER BY canonical, pattern"
+        )
+        .fetch_all(&data.db)
+        .await?;
+        Ok(aliases)
+    }
+
+    /// `LIKE` pattern that matches the `survey_device_key` of the device names that
+    /// `pattern` matches
+    pub async fn match_key(pattern: &str, data: &AppData) -> ServiceResult<String> {
+        let res = sqlx::query!(
+            r#"SELECT survey_device_match_key($1) AS "match_key!""#,
+            pattern,
+        )
+        .fetch_one(&data.db)
+        .await?;
+        Ok(res.match_key)
+    }
+
+    pub async fn add(alias: &AddDeviceAlias, data: &AppData) -> ServiceResult<()> {
+        let match_key = match_key(&alias.pattern, data).await?;
+        alias.validate(&match_key)?;
+        let mut tx = data.db.begin().await?;
+        let res = sqlx::query!(
+            "INSERT INTO survey_device_aliases
+                (pattern, match_key, canonical, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (match_key) DO NOTHING",
+            &alias.pattern,
+            &match_key,
+            alias.canonical.trim(),
+            OffsetDateTime::now_utc(),
+        )
+        .execute(&mut tx)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            return Err(ServiceError::DeviceAliasExists);
+        }
+        update_canonical_devices(&match_key, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn remove(id: i32, data: &AppData) -> ServiceResult<()> {
+        let mut tx = data.db.begin().await?;
+        let removed = sqlx::query!(
+            "DELETE FROM survey_device_aliases WHERE ID = $1 RETURNING match_key",
+            id
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .ok_or(ServiceError::DeviceAliasDoesntExist)?;
+
+        update_canonical_devices(&removed.match_key, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// recomputes `survey_responses.canonical_device` of the responses whose device
+    /// name matches an alias's `match_key`, after the alias was added or removed
+    async fn update_canonical_devices(
+        match_key: &str,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> ServiceResult<()> {
+        sqlx::query!(
+            "UPDATE survey_responses SET
+                canonical_device = survey_canonical_device(device_user_provided)
+            WHERE
+                survey_device_key(device_user_provided) LIKE $1",
+            match_key,
+        )
+        .execute(&mut *tx)
+        .await?;
+        Ok(())
+    }
+
+    /// suggested groups of the device names, without an alias, of the responses
+    /// to campaigns that `username` can view
+    pub async fn suggestions(
+        username: &str,
+        data: &AppData,
+    ) -> ServiceResult<Vec<DeviceGroup>> {
+        struct InnerDevice {
+            name: String,
+            key: String,
+            responses: i64,
+        }
+
+        let mut devices = sqlx::query_as!(
+            InnerDevice,
+            r#"SELECT
+                survey_responses.device_user_provided AS name,
+                survey_device_key(survey_responses.device_user_provided) AS "key!",
+                COUNT(*) AS "responses!"
+            FROM
+                survey_responses
+            INNER JOIN survey_campaigns ON
+                survey_campaigns.ID = survey_responses.campaign_id
+            WHERE
+                survey_campaigns.deleted_at IS NULL
+            AND (
+                survey_campaigns.user_id = (
+                    SELECT ID FROM survey_admins WHERE name = $1
+                )
+            OR
+                survey_campaigns.ID IN (
+                    SELECT survey_campaign_members.campaign_id
+                    FROM survey_campaign_members
+                    INNER JOIN survey_admins ON
+                        survey_admins.ID = survey_campaign_members.user_id
+                    WHERE
+                        survey_admins.name = $1
+                )
+            )
+            AND
+                survey_responses.canonical_device IS NULL
+            GROUP BY
+                survey_responses.device_user_provided
+            ORDER BY
+                COUNT(*) DESC, survey_responses.device_user_provided"#,
+            username,
+        )
+        .fetch_all(&data.db)
+        .await?;
+        Ok(suggest_groups(
+            devices
+                .drain(0..)
+                .map(|d| {
+                    let device = DeviceCount {
+                        name: d.name,
+                        responses: d.responses,
+                    };
+                    (d.key, device)
+                })
+                .collect(),
+        ))
+    }
+}
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(list);
+    cfg.service(add);
+    cfg.service(remove);
+    cfg.service(suggestions);
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.devices.list",
+    wrap = "get_admin_check_login()"
+)]
+async fn list(data: AppData) -> ServiceResult<impl Responder> {
+    let aliases = runners::list(&data).await?;
+    Ok(HttpResponse::Ok().json(aliases))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.devices.add",
+    wrap = "get_admin_check_login()"
+)]
+async fn add(
+    id: Identity,
+    payload: web::Json<AddDeviceAlias>,
+    data: AppData,
+) -> ServiceResult<impl Responder> {
+    require_site_admin(&id.identity().unwrap(), &data)?;
+    runners::add(&payload, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.devices.remove",
+    wrap = "get_admin_check_login()"
+)]
+async fn remove(
+    id: Identity,
+    payload: web::Json<RemoveDeviceAlias>,
+    data: AppData,
+) -> ServiceResult<impl Responder> {
+    require_site_admin(&id.identity().unwrap(), &data)?;
+    runners::remove(payload.id, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.devices.suggestions",
+    wrap = "get_admin_check_login()"
+)]
+async fn suggestions(id: Identity, data: AppData) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let groups = runners::suggestions(&username, &data).await?;
+    Ok(HttpResponse::Ok().json(groups))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use actix_web::ResponseError;
+
+    use super::*;
+    use crate::api::v1::admin::campaigns::{runners::get_results, ResultsPage};
+    use crate::api::v1::admin::stats::{CampaignHashrate, CampaignStats};
+    use crate::api::v1::bench::{Submission, SubmissionType};
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+    use sqlx::types::Uuid;
+
+    #[actix_rt::test]
+    async fn device_alias_match_key_works() {
+        let data = AppData::new(get_test_data().await);
+        for (pattern, key) in [
+            ("Pixel 6 (Pro)", "pixel6pro"),
+            ("*Galaxy S21**", "%galaxys21%"),
+            ("100% phone", "100phone"),
+            ("-- * --", "%"),
+        ] {
+            assert_eq!(runners::match_key(pattern, &data).await.unwrap(), key);
+        }
+
+        let alias = |pattern: &str, canonical: &str| AddDeviceAlias {
+            pattern: pattern.into(),
+            canonical: canonical.into(),
+        };
+        assert!(alias("Pixel 6", "Pixel 6").validate("pixel6").is_ok());
+        for (invalid, key) in [
+            (alias("*", "foo"), "%"),
+            (alias("Pixel 6", "  "), "pixel6"),
+            (
+                alias("Pixel 6", &"a".repeat(MAX_CANONICAL_DEVICE_LEN + 1)),
+                "pixel6",
+            ),
+            (alias(&"a".repeat(MAX_DEVICE_INFO_LEN + 1), "foo"), "aaa"),
+        ] {
+            assert_eq!(invalid.validate(key), Err(ServiceError::InvalidDeviceAlias));
+        }
+    }
+
+    #[test]
+    fn suggest_groups_works() {
+        let count = |name: &str, responses| DeviceCount {
+            name: name.into(),
+            responses,
+        };
+        let device =
+            |key: &str, name, responses| (key.to_string(), count(name, responses));
+
+        let groups = suggest_groups(vec![
+            device("pixel6", "Pixel 6", 10),
+            device("iphone12", "iPhone 12", 8),
+            device("pixel6", "pixel6", 5),
+            device("googlepixel6pro", "Google Pixel 6 Pro", 3),
+            device("iphone12", "iphone12", 2),
+            device("motog", "Moto G", 2),
+            device("", "???", 1),
+        ]);
+        assert_eq!(
+            groups,
+            vec![
+                DeviceGroup {
+                    canonical: "Pixel 6".into(),
+                    devices: vec![
+                        count("Pixel 6", 10),
+                        count("pixel6", 5),
+                        count("Google Pixel 6 Pro", 3),
+                    ],
+                },
+                DeviceGroup {
+                    canonical: "iPhone 12".into(),
+                    devices: vec![count("iPhone 12", 8), count("iphone12", 2)],
+                },
+            ]
+        );
+        assert!(similarity("pixel6", "pixel6") > 0.99);
+        assert!(similarity("pixel6", "motog") < SUGGESTION_SIMILARITY);
+    }
+
+    #[actix_rt::test]
+    async fn device_aliases_work() {
+        const NAME: &str = "devicealiasesuser";
+        const EMAIL: &str = "devicealiasesuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+        const CANONICAL: &str = "DeviceAliasesUser Phone 7";
+        const DEVICES: [&str; 3] = [
+            "DeviceAliasesUser Phone 7",
+            "devicealiasesuser-phone7",
+            "Device Aliases User Phone 7 Pro",
+        ];
+
+        let (data, _creds, signin_resp) = {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+            sqlx::query!(
+                "DELETE FROM survey_device_aliases WHERE canonical = $1",
+                CANONICAL
+            )
+            .execute(&data.db)
+            .await
+            .unwrap();
+            register_and_signin(NAME, EMAIL, PASSWORD).await
+        };
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        for device in DEVICES {
+            let submit_payload = Submission {
+                device_user_provided: device.into(),
+                device_software_recognised: NAME.into(),
+                threads: 4,
+                benches: solve_benches(
+                    &campaign,
+                    data.clone(),
+                    survey_cookie.clone(),
+                    &BENCHES,
+                )
+                .await,
+                submission_type: SubmissionType::wasm(),
+                idempotency_key: None,
+                hints: DeviceHints::default(),
+            };
+            submit_bench(
+                &submit_payload,
+                &campaign,
+                survey_cookie.clone(),
+                data.clone(),
+            )
+            .await;
+        }
+
+        let resp = get_request!(
+            &app,
+            V1_API_ROUTES.admin.devices.suggestions,
+            cookies.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let groups: Vec<DeviceGroup> = test::read_body_json(resp).await;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].devices.len(), DEVICES.len());
+
+        let add = AddDeviceAlias {
+            pattern: "deviceAliasesUser phone 7".into(),
+            canonical: CANONICAL.into(),
+        };
+        // aliases apply to all campaigns, so only site admins change them
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.devices.add,
+            &add,
+            ServiceError::SiteAdminRequired,
+        )
+        .await;
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.devices.remove,
+            &RemoveDeviceAlias { id: 0 },
+            ServiceError::SiteAdminRequired,
+        )
+        .await;
+        let data = get_site_admin_data(NAME).await;
+        let app = get_app!(data).await;
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, V1_API_ROUTES.admin.devices.add)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let duplicate = AddDeviceAlias {
+            pattern: "device-aliases-user-phone-7".into(),
+            canonical: "foo".into(),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&duplicate, V1_API_ROUTES.admin.devices.add)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::DeviceAliasExists.status_code());
+
+        let resp = get_request!(&app, V1_API_ROUTES.admin.devices.list, cookies.clone());
+        let aliases: Vec<DeviceAlias> = test::read_body_json(resp).await;
+        let alias = aliases.iter().find(|a| a.canonical == CANONICAL).unwrap();
+        assert_eq!(alias.pattern, add.pattern);
+
+        let uuid = Uuid::parse_str(&campaign.campaign_id).unwrap();
+        let responses = get_results(
+            NAME,
+            &uuid,
+            &AppData::new(data.clone()),
+            &ResultsPage::default(),
+            10,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert_eq!(responses.len(), DEVICES.len());
+        for r in responses.iter() {
+            let expected = if r.device_user_provided == DEVICES[2] {
+                None
+            } else {
+                Some(CANONICAL.to_string())
+            };
+            assert_eq!(r.canonical_device, expected);
+        }
+
+        // the device filter matches canonical names too
+        let filtered = get_results(
+            NAME,
+            &uuid,
+            &AppData::new(data.clone()),
+            &ResultsPage {
+                device: Some("aliasesuser phone 7".into()),
+                ..Default::default()
+            },
+            10,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert_eq!(filtered.len(), 2);
+
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES
+                .admin
+                .stats
+                .get_summary_route(&campaign.campaign_id),
+            cookies.clone()
+        );
+        let stats: CampaignStats = test::read_body_json(resp).await;
+        assert_eq!(stats.devices.len(), 2);
+        assert_eq!(
+            stats.devices[0].canonical_device.as_deref(),
+            Some(CANONICAL)
+        );
+        assert_eq!(stats.devices[0].responses, 2);
+        assert_eq!(stats.devices[1].canonical_device, None);
+        assert_eq!(stats.devices[1].responses, 1);
+
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES
+                .admin
+                .stats
+                .get_hashrate_route(&campaign.campaign_id),
+            cookies.clone()
+        );
+        let hashrate: CampaignHashrate = test::read_body_json(resp).await;
+        let devices = &hashrate.submission_types[0].devices;
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].canonical_device, CANONICAL);
+        assert_eq!(devices[0].hashrate.responses, 2);
+
+        // aliased names aren't suggested anymore
+        let resp = get_request!(
+            &app,
+            V1_API_ROUTES.admin.devices.suggestions,
+            cookies.clone()
+        );
+        let groups: Vec<DeviceGroup> = test::read_body_json(resp).await;
+        assert!(groups.is_empty());
+
+        let remove = RemoveDeviceAlias { id: alias.id };
+        for status in [
+            StatusCode::OK,
+            ServiceError::DeviceAliasDoesntExist.status_code(),
+        ] {
+            let resp = test::call_service(
+                &app,
+                post_request!(&remove, V1_API_ROUTES.admin.devices.remove)
+                    .cookie(cookies.clone())
+                    .to_request(),
+            )
+            .await;
+            assert_eq!(resp.status(), status);
+        }
+        // the responses that matched the removed alias lose its canonical name
+        let responses = get_results(
+            NAME,
+            &uuid,
+            &AppData::new(data.clone()),
+            &ResultsPage::default(),
+            10,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert!(responses.iter().all(|r| r.canonical_device.is_none()));
+    }
+}
@@ -0,0 +1,602 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Sharing campaigns with other admins. A campaign's creator is always its owner;
+//! other admins are invited as owners, editors or viewers through
+//! `survey_campaign_members`.
+use std::str::FromStr;
+
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+
+use super::get_admin_check_login;
+use crate::errors::*;
+use crate::AppData;
+
+pub mod routes {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+    pub struct Members {
+        pub list: &'static str,
+        pub add: &'static str,
+        pub remove: &'static str,
+    }
+
+    impl Members {
+        pub const fn new() -> Members {
+            let list = "/admin/api/v1/campaign/{uuid}/members";
+            let add = "/admin/api/v1/campaign/{uuid}/members/add";
+            let remove = "/admin/api/v1/campaign/{uuid}/members/remove";
+            Members { list, add, remove }
+        }
+
+        pub fn get_list_route(&self, campaign_id: &str) -> String {
+            self.list.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_add_route(&self, campaign_id: &str) -> String {
+            self.add.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_remove_route(&self, campaign_id: &str) -> String {
+            self.remove.replace("{uuid}", campaign_id)
+        }
+    }
+}
+
+/// What an admin can do with a campaign. Each role can do everything the roles
+/// before it can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CampaignRole {
+    /// read results and exports
+    Viewer,
+    /// change the campaign's configuration and lifecycle
+    Editor,
+    /// delete the campaign and manage its members
+    Owner,
+}
+
+impl ToString for CampaignRole {
+    fn to_string(&self) -> String {
+        let s = serde_json::to_string(&self).unwrap();
+        (&s[1..(s.len() - 1)]).to_string()
+    }
+}
+
+impl FromStr for CampaignRole {
+    type Err = serde_json::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(&format!("\"{}\"", s))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignMember {
+    pub username: String,
+    pub role: CampaignRole,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddMember {
+    /// username or email of an existing admin
+    pub user: String,
+    pub role: CampaignRole,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoveMember {
+    pub username: String,
+}
+
+pub mod runners {
+    use super::*;
+
+    /// Role of `username` on campaign `uuid`. Campaigns that aren't shared with
+    /// `username`, or that are in the trash, don't exist as far as they are concerned.
+    pub async fn get_role(
+        username: &str,
+        uuid: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<CampaignRole> {
+        struct InnerRole {
+            role: Option<String>,
+        }
+
+        let res = sqlx::query_as!(
+            InnerRole,
+            "SELECT
+                CASE
+                    WHEN survey_campaigns.user_id = survey_admins.ID THEN 'owner'
+                    ELSE survey_campaign_roles.name
+                END AS role
+            FROM
+                survey_campaigns
+            INNER JOIN survey_admins ON
+                survey_admins.name = $2
+            LEFT JOIN survey_campaign_members ON
+                survey_campaign_members.campaign_id = survey_campaigns.ID
+            AND
+                survey_campaign_members.user_id = survey_admins.ID
+            LEFT JOIN survey_campaign_roles ON
+                survey_campaign_roles.ID = survey_campaign_members.role_id
+            WHERE
+                survey_campaigns.ID = $1
+            AND
+                survey_campaigns.deleted_at IS NULL",
+            uuid,
+            username,
+        )
+        .fetch_optional(&data.db)
+        .await?;
+
+        match res.and_then(|r| r.role) {
+            Some(role) => Ok(CampaignRole::from_str(&role).unwrap()),
+            None => Err(ServiceError::CampaignDoesntExist),
+        }
+    }
+
+    /// errors unless `username` has at least `required` role on campaign `uuid`
+    pub async fn require_role(
+        username: &str,
+        uuid: &Uuid,
+        required: CampaignRole,
+        data: &AppData,
+    ) -> ServiceResult<CampaignRole> {
+        let role = get_role(username, uuid, data).await?;
+        if role < required {
+            Err(ServiceError::CampaignPermissionDenied)
+        } else {
+            Ok(role)
+        }
+    }
+
+    /// campaign's creator, followed by the admins it is shared with
+    pub async fn list(
+        uuid: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<Vec<CampaignMember>> {
+        struct InnerMember {
+            name: String,
+            role: String,
+        }
+
+        let mut members = sqlx::query_as!(
+            InnerMember,
+            r#"SELECT
+                survey_admins.name AS "name!", 'owner' AS "role!"
+            FROM
+                survey_campaigns
+            INNER JOIN survey_admins ON
+                survey_admins.ID = survey_campaigns.user_id
+            WHERE
+                survey_campaigns.ID = $1
+            UNION ALL
+            (
+                SELECT
+                    survey_admins.name AS "name!", survey_campaign_roles.name AS "role!"
+                FROM
+                    survey_campaign_members
+                INNER JOIN survey_admins ON
+                    survey_admins.ID = survey_campaign_members.user_id
+                INNER JOIN survey_campaign_roles ON
+                    survey_campaign_roles.ID = survey_campaign_members.role_id
+                WHERE
+                    survey_campaign_members.campaign_id = $1
+                ORDER BY
+                    survey_campaign_members.ID
+            )"#,
+            uuid,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        Ok(members
+            .drain(0..)
+            .map(|m| CampaignMember {
+                username: m.name,
+                role: CampaignRole::from_str(&m.role).unwrap(),
+            })
+            .collect())
+    }
+
+    /// Shares campaign `uuid` with the admin whose username or email is
+    /// `payload.user`. Changes their role if it is already shared with them.
+    pub async fn add(
+        uuid: &Uuid,
+        payload: &AddMember,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        struct InnerAdmin {
+            id: i32,
+            is_creator: Option<bool>,
+        }
+
+        let admin = sqlx::query_as!(
+            InnerAdmin,
+            "SELECT
+                survey_admins.ID,
+                survey_admins.ID = (
+                    SELECT user_id FROM survey_campaigns WHERE ID = $2
+                ) AS is_creator
+            FROM
+                survey_admins
+            WHERE
+                survey_admins.name = $1 OR survey_admins.email = $1",
+            &payload.user,
+            uuid,
+        )
+        .fetch_optional(&data.db)
+        .await?
+        .ok_or(ServiceError::AccountNotFound)?;
+
+        if admin.is_creator.unwrap_or(false) {
+            return Err(ServiceError::CampaignCreatorRole);
+        }
+
+        sqlx::query!(
+            "INSERT INTO survey_campaign_members (campaign_id, user_id, role_id)
+            VALUES (
+                $1, $2, (SELECT ID FROM survey_campaign_roles WHERE name = $3)
+            )
+            ON CONFLICT (campaign_id, user_id) DO UPDATE SET
+                role_id = EXCLUDED.role_id",
+            uuid,
+            admin.id,
+            payload.role.to_string(),
+        )
+        .execute(&data.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove(
+        uuid: &Uuid,
+        username: &str,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        struct IsCreator {
+            exists: Option<bool>,
+        }
+        let creator = sqlx::query_as!(
+            IsCreator,
+            "SELECT EXISTS (
+                SELECT 1 FROM survey_campaigns
+                INNER JOIN survey_admins ON
+                    survey_admins.ID = survey_campaigns.user_id
+                WHERE
+                    survey_campaigns.ID = $1
+                AND
+                    survey_admins.name = $2
+            );",
+            uuid,
+            username,
+        )
+        .fetch_one(&data.db)
+        .await?;
+        if creator.exists.unwrap_or(false) {
+            return Err(ServiceError::CampaignCreatorRole);
+        }
+
+        let res = sqlx::query!(
+            "DELETE FROM survey_campaign_members
+            WHERE
+                campaign_id = $1
+            AND
+                user_id = (SELECT ID FROM survey_admins WHERE name = $2)",
+            uuid,
+            username,
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::AccountNotFound)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(list);
+    cfg.service(add);
+    cfg.service(remove);
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.members.list",
+    wrap = "get_admin_check_login()"
+)]
+async fn list(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    runners::require_role(&username, &uuid, CampaignRole::Viewer, &data).await?;
+    let members = runners::list(&uuid, &data).await?;
+    Ok(HttpResponse::Ok().json(members))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.members.add",
+    wrap = "get_admin_check_login()"
+)]
+async fn add(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    payload: web::Json<AddMember>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    runners::require_role(&username, &uuid, CampaignRole::Owner, &data).await?;
+    runners::add(&uuid, &payload, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.members.remove",
+    wrap = "get_admin_check_login()"
+)]
+async fn remove(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    payload: web::Json<RemoveMember>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    runners::require_role(&username, &uuid, CampaignRole::Owner, &data).await?;
+    runners::remove(&uuid, &payload.username, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+
+    use super::{AddMember, CampaignMember, CampaignRole, RemoveMember};
+    use crate::api::v1::admin::campaigns::{
+        CampaignLifecycle, CampaignState, CloneCampaign,
+    };
+    use crate::errors::*;
+    use crate::tests::*;
+    use crate::*;
+
+    #[actix_rt::test]
+    async fn campaign_members_work() {
+        const NAME: &str = "membersowner";
+        const EMAIL: &str = "membersowner@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+        const MEMBER: &str = "membersmember";
+        const MEMBER_EMAIL: &str = "membersmember@testadminuser.com";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+            delete_user(MEMBER, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let (_, _, signin_resp) =
+            register_and_signin(MEMBER, MEMBER_EMAIL, PASSWORD).await;
+        let member_cookies = get_cookie!(signin_resp);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let results_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_results_route(&campaign.campaign_id, None);
+        let lifecycle_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_lifecycle_route(&campaign.campaign_id);
+        let add_route = V1_API_ROUTES
+            .admin
+            .members
+            .get_add_route(&campaign.campaign_id);
+        let remove_route = V1_API_ROUTES
+            .admin
+            .members
+            .get_remove_route(&campaign.campaign_id);
+        let lifecycle = CampaignLifecycle {
+            state: CampaignState::Open,
+            starts_at: None,
+            ends_at: None,
+            max_responses: None,
+            max_responses_per_participant: None,
+        };
+
+        // campaigns that aren't shared are invisible
+        let resp = get_request!(&app, &results_route, member_cookies.clone());
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let list = list_campaings(data.clone(), member_cookies.clone()).await;
+        assert!(!list.iter().any(|c| c.uuid == campaign.campaign_id));
+
+        let mut add = AddMember {
+            user: MEMBER_EMAIL.into(),
+            role: CampaignRole::Viewer,
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, &add_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let list = list_campaings(data.clone(), member_cookies.clone()).await;
+        assert!(list
+            .iter()
+            .any(|c| c.uuid == campaign.campaign_id
+                && c.role == Some(CampaignRole::Viewer)));
+        let resp = get_request!(&app, &results_route, member_cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES
+                .admin
+                .members
+                .get_list_route(&campaign.campaign_id),
+            member_cookies.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let members: Vec<CampaignMember> = test::read_body_json(resp).await;
+        assert_eq!(
+            members,
+            vec![
+                CampaignMember {
+                    username: NAME.into(),
+                    role: CampaignRole::Owner,
+                },
+                CampaignMember {
+                    username: MEMBER.into(),
+                    role: CampaignRole::Viewer,
+                },
+            ]
+        );
+
+        // viewers can only read
+        bad_post_req_test(
+            MEMBER,
+            PASSWORD,
+            &lifecycle_route,
+            &lifecycle,
+            ServiceError::CampaignPermissionDenied,
+        )
+        .await;
+        bad_post_req_test(
+            MEMBER,
+            PASSWORD,
+            &add_route,
+            &add,
+            ServiceError::CampaignPermissionDenied,
+        )
+        .await;
+        bad_post_req_test_witout_payload(
+            MEMBER,
+            PASSWORD,
+            &V1_API_ROUTES
+                .admin
+                .campaign
+                .get_delete_route(&campaign.campaign_id),
+            ServiceError::CampaignPermissionDenied,
+        )
+        .await;
+        let clone_route = V1_API_ROUTES
+            .admin
+            .campaign
+            .get_clone_route(&campaign.campaign_id);
+        let clone = CloneCampaign { name: None };
+        bad_post_req_test(
+            MEMBER,
+            PASSWORD,
+            &clone_route,
+            &clone,
+            ServiceError::CampaignPermissionDenied,
+        )
+        .await;
+
+        // inviting again changes the role
+        add.user = MEMBER.into();
+        add.role = CampaignRole::Editor;
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, &add_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(
+            &app,
+            post_request!(&lifecycle, &lifecycle_route)
+                .cookie(member_cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(
+            &app,
+            post_request!(&clone, &clone_route)
+                .cookie(member_cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        add.user = "doesntexist".into();
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &add_route,
+            &add,
+            ServiceError::AccountNotFound,
+        )
+        .await;
+        add.user = NAME.into();
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &add_route,
+            &add,
+            ServiceError::CampaignCreatorRole,
+        )
+        .await;
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &remove_route,
+            &RemoveMember {
+                username: NAME.into(),
+            },
+            ServiceError::CampaignCreatorRole,
+        )
+        .await;
+
+        let remove = RemoveMember {
+            username: MEMBER.into(),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&remove, &remove_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = get_request!(&app, &results_route, member_cookies.clone());
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            &remove_route,
+            &remove,
+            ServiceError::AccountNotFound,
+        )
+        .await;
+    }
+}
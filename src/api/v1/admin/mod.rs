@@ -17,9 +17,18 @@
 use actix_auth_middleware::*;
 use actix_web::web::ServiceConfig;
 
+use crate::errors::*;
+use crate::AppData;
+
 pub mod account;
 pub mod auth;
+pub mod bench_types;
 pub mod campaigns;
+pub mod devices;
+pub mod members;
+pub mod review;
+pub mod stats;
+pub mod templates;
 #[cfg(test)]
 mod tests;
 
@@ -28,24 +37,56 @@ pub use super::{get_random, get_uuid, RedirectQuery};
 pub fn services(cfg: &mut ServiceConfig) {
     auth::services(cfg);
     account::services(cfg);
+    bench_types::services(cfg);
     campaigns::services(cfg);
+    devices::services(cfg);
+    members::services(cfg);
+    review::services(cfg);
+    stats::services(cfg);
+    templates::services(cfg);
 }
 
 pub fn get_admin_check_login() -> Authentication<auth::routes::Auth> {
     Authentication::with_identity(super::ROUTES.admin.auth)
 }
 
+/// errors unless `username` is one of the site admins listed in the settings
+pub fn require_site_admin(username: &str, data: &AppData) -> ServiceResult<()> {
+    if data
+        .settings
+        .site_admins
+        .iter()
+        .any(|admin| admin == username)
+    {
+        Ok(())
+    } else {
+        Err(ServiceError::SiteAdminRequired)
+    }
+}
+
 pub mod routes {
     use super::account::routes::Account;
     use super::auth::routes::Auth;
+    use super::bench_types::routes::BenchTypes;
     use super::campaigns::routes::Campaign;
+    use super::devices::routes::Devices;
+    use super::members::routes::Members;
+    use super::review::routes::Review;
+    use super::stats::routes::Stats;
+    use super::templates::routes::Templates;
     use serde::Serialize;
 
     #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
     pub struct Admin {
         pub auth: Auth,
         pub account: Account,
+        pub bench_types: BenchTypes,
         pub campaign: Campaign,
+        pub devices: Devices,
+        pub members: Members,
+        pub review: Review,
+        pub stats: Stats,
+        pub templates: Templates,
     }
 
     impl Admin {
@@ -53,7 +94,13 @@ pub mod routes {
             Admin {
                 account: Account::new(),
                 auth: Auth::new(),
+                bench_types: BenchTypes::new(),
                 campaign: Campaign::new(),
+                devices: Devices::new(),
+                members: Members::new(),
+                review: Review::new(),
+                stats: Stats::new(),
+                templates: Templates::new(),
             }
         }
     }
@@ -0,0 +1,467 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Sanity rules that submissions are checked against when they are received, and
+//! the queue in which admins accept or exclude the submissions that break them.
+//! Excluded responses are kept, but left out of statistics and published archives.
+use std::str::FromStr;
+
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Uuid;
+
+use super::campaigns::{runners::get_results, ResultsPage, SurveyResults};
+use super::get_admin_check_login;
+use super::members::{self, CampaignRole};
+use crate::api::v1::bench::{Bench, BenchSummary};
+use crate::errors::*;
+use crate::settings::Sanity;
+use crate::AppData;
+
+/// responses in a page of the review queue
+pub const QUEUE_LIMIT: usize = 50;
+
+pub mod routes {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+    pub struct Review {
+        pub queue: &'static str,
+        pub decide: &'static str,
+    }
+
+    impl Review {
+        pub const fn new() -> Review {
+            let queue = "/admin/api/v1/campaign/{uuid}/review";
+            let decide = "/admin/api/v1/campaign/{uuid}/review/decide";
+            Review { queue, decide }
+        }
+
+        pub fn get_queue_route(&self, campaign_id: &str) -> String {
+            self.queue.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_decide_route(&self, campaign_id: &str) -> String {
+            self.decide.replace("{uuid}", campaign_id)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewState {
+    /// broke no sanity rule, or was accepted by an admin
+    Accepted,
+    /// broke a sanity rule and is waiting for review
+    Flagged,
+    /// left out of statistics and published archives by an admin
+    Excluded,
+}
+
+impl ToString for ReviewState {
+    fn to_string(&self) -> String {
+        let s = serde_json::to_string(&self).unwrap();
+        (&s[1..(s.len() - 1)]).to_string()
+    }
+}
+
+impl FromStr for ReviewState {
+    type Err = serde_json::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(&format!("\"{}\"", s))
+    }
+}
+
+/// sanity rule that a submission broke
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanityFlag {
+    /// median duration drops as difficulty rises
+    DecreasingDurations,
+    /// implausibly quick solve at a high difficulty
+    FastSolve,
+    /// every bench took exactly as long
+    IdenticalDurations,
+}
+
+impl ToString for SanityFlag {
+    fn to_string(&self) -> String {
+        let s = serde_json::to_string(&self).unwrap();
+        (&s[1..(s.len() - 1)]).to_string()
+    }
+}
+
+impl FromStr for SanityFlag {
+    type Err = serde_json::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(&format!("\"{}\"", s))
+    }
+}
+
+impl SanityFlag {
+    /// rules of `sanity` that `benches` break
+    pub fn check(sanity: &Sanity, benches: &[Bench]) -> Vec<SanityFlag> {
+        let mut flags = Vec::new();
+
+        let summary = BenchSummary::summarize(benches);
+        if summary
+            .windows(2)
+            .any(|w| w[1].median < w[0].median * (1.0 - sanity.decrease_tolerance))
+        {
+            flags.push(SanityFlag::DecreasingDurations);
+        }
+
+        if benches.iter().any(|b| {
+            b.difficulty >= sanity.fast_solve_difficulty
+                && b.duration < sanity.fast_solve_duration
+        }) {
+            flags.push(SanityFlag::FastSolve);
+        }
+
+        if sanity.identical_durations
+            && benches.len() > 1
+            && benches.iter().all(|b| b.duration == benches[0].duration)
+        {
+            flags.push(SanityFlag::IdenticalDurations);
+        }
+
+        flags
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    pub state: ReviewState,
+    /// rules that the response broke when it was received
+    pub flags: Vec<SanityFlag>,
+    /// UNIX timestamp of the last decision, `None` if no admin has reviewed it
+    pub reviewed_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Accept,
+    Exclude,
+}
+
+impl From<Decision> for ReviewState {
+    fn from(d: Decision) -> Self {
+        match d {
+            Decision::Accept => ReviewState::Accepted,
+            Decision::Exclude => ReviewState::Excluded,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewDecision {
+    /// ID of the response, see [SurveyResponse::id](super::campaigns::SurveyResponse::id)
+    pub id: i32,
+    pub decision: Decision,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueQuery {
+    /// cursor of the previous page, see [SurveyResults::next]
+    pub after: Option<String>,
+}
+
+pub mod runners {
+    use super::*;
+
+    /// flagged responses of campaign `uuid` that are waiting for review, oldest
+    /// first
+    pub async fn queue(
+        username: &str,
+        uuid: &Uuid,
+        after: Option<String>,
+        data: &AppData,
+    ) -> ServiceResult<SurveyResults> {
+        let page = ResultsPage {
+            after,
+            review: Some(ReviewState::Flagged),
+            ..Default::default()
+        };
+        get_results(username, uuid, data, &page, QUEUE_LIMIT).await
+    }
+
+    /// Accept or exclude a response of campaign `uuid`. Responses can be reviewed
+    /// again, so decisions can be undone.
+    pub async fn decide(
+        uuid: &Uuid,
+        decision: &ReviewDecision,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        let state: ReviewState = decision.decision.into();
+        let res = sqlx::query!(
+            "UPDATE survey_responses SET
+                review_state_id = (
+                    SELECT ID FROM survey_response_review_state WHERE name = $1
+                ),
+                reviewed_at = $2
+            WHERE
+                ID = $3
+            AND
+                campaign_id = $4",
+            state.to_string(),
+            OffsetDateTime::now_utc(),
+            decision.id,
+            uuid,
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::SubmissionNotFound)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(queue);
+    cfg.service(decide);
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.review.queue",
+    wrap = "get_admin_check_login()"
+)]
+async fn queue(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    query: web::Query<QueueQuery>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    let results =
+        runners::queue(&username, &uuid, query.into_inner().after, &data).await?;
+    Ok(HttpResponse::Ok().json(results))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.review.decide",
+    wrap = "get_admin_check_login()"
+)]
+async fn decide(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    payload: web::Json<ReviewDecision>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    members::runners::require_role(&username, &uuid, CampaignRole::Editor, &data)
+        .await?;
+    runners::decide(&uuid, &payload, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use actix_web::ResponseError;
+
+    use super::*;
+    use crate::api::v1::admin::stats::CampaignStats;
+    use crate::api::v1::bench::{Submission, SubmissionType};
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+
+    #[test]
+    fn sanity_flags_work() {
+        let sanity = Sanity {
+            decrease_tolerance: 0.1,
+            fast_solve_difficulty: 1000,
+            fast_solve_duration: 1.0,
+            identical_durations: true,
+        };
+        let bench = |difficulty, duration| Bench {
+            difficulty,
+            duration,
+        };
+
+        let ok = [bench(10, 10.0), bench(100, 9.5), bench(1000, 100.0)];
+        assert!(SanityFlag::check(&sanity, &ok).is_empty());
+
+        let decreasing = [bench(10, 10.0), bench(100, 8.0), bench(1000, 100.0)];
+        assert_eq!(
+            SanityFlag::check(&sanity, &decreasing),
+            vec![SanityFlag::DecreasingDurations]
+        );
+
+        let fast = [bench(10, 0.1), bench(100, 0.5), bench(1000, 0.9)];
+        assert_eq!(
+            SanityFlag::check(&sanity, &fast),
+            vec![SanityFlag::FastSolve]
+        );
+
+        let identical = [bench(10, 5.0), bench(100, 5.0), bench(1000, 5.0)];
+        assert_eq!(
+            SanityFlag::check(&sanity, &identical),
+            vec![SanityFlag::IdenticalDurations]
+        );
+        let sanity = Sanity {
+            identical_durations: false,
+            ..sanity
+        };
+        assert!(SanityFlag::check(&sanity, &identical).is_empty());
+        assert!(SanityFlag::check(&sanity, &[]).is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn review_queue_works() {
+        const NAME: &str = "reviewqueueuser";
+        const EMAIL: &str = "reviewqueueuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+
+        let identical: Vec<Bench> = BENCHES
+            .iter()
+            .map(|b| Bench {
+                difficulty: b.difficulty,
+                duration: 3.0,
+            })
+            .collect();
+        for benches in [BENCHES.clone(), identical] {
+            let submit_payload = Submission {
+                device_user_provided: "foo".into(),
+                device_software_recognised: "reviewqueueuser".into(),
+                threads: 4,
+                benches: solve_benches(
+                    &campaign,
+                    data.clone(),
+                    survey_cookie.clone(),
+                    &benches,
+                )
+                .await,
+                submission_type: SubmissionType::wasm(),
+                idempotency_key: None,
+                hints: DeviceHints::default(),
+            };
+            submit_bench(
+                &submit_payload,
+                &campaign,
+                survey_cookie.clone(),
+                data.clone(),
+            )
+            .await;
+        }
+
+        let queue_route = V1_API_ROUTES
+            .admin
+            .review
+            .get_queue_route(&campaign.campaign_id);
+        let resp = get_request!(&app, &queue_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let queue: SurveyResults = test::read_body_json(resp).await;
+        assert_eq!(queue.responses.len(), 1);
+        let flagged = &queue.responses[0];
+        assert_eq!(flagged.review.state, ReviewState::Flagged);
+        assert_eq!(flagged.review.flags, vec![SanityFlag::IdenticalDurations]);
+        assert!(flagged.review.reviewed_at.is_none());
+
+        let stats_route = V1_API_ROUTES
+            .admin
+            .stats
+            .get_summary_route(&campaign.campaign_id);
+        let resp = get_request!(&app, &stats_route, cookies.clone());
+        let stats: CampaignStats = test::read_body_json(resp).await;
+        assert_eq!(stats.responses, 2);
+
+        let decide_route = V1_API_ROUTES
+            .admin
+            .review
+            .get_decide_route(&campaign.campaign_id);
+        let missing = ReviewDecision {
+            id: 0,
+            decision: Decision::Exclude,
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&missing, &decide_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(
+            resp.status(),
+            ServiceError::SubmissionNotFound.status_code()
+        );
+
+        let exclude = ReviewDecision {
+            id: i32::try_from(flagged.id).unwrap(),
+            decision: Decision::Exclude,
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&exclude, &decide_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = get_request!(&app, &queue_route, cookies.clone());
+        let queue: SurveyResults = test::read_body_json(resp).await;
+        assert!(queue.responses.is_empty());
+
+        // excluded responses are kept, but left out of statistics
+        let resp = get_request!(&app, &stats_route, cookies.clone());
+        let stats: CampaignStats = test::read_body_json(resp).await;
+        assert_eq!(stats.responses, 1);
+
+        let uuid = Uuid::parse_str(&campaign.campaign_id).unwrap();
+        let excluded = get_results(
+            NAME,
+            &uuid,
+            &AppData::new(data.clone()),
+            &ResultsPage {
+                review: Some(ReviewState::Excluded),
+                ..Default::default()
+            },
+            10,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].id, flagged.id);
+        assert!(excluded[0].review.reviewed_at.is_some());
+    }
+}
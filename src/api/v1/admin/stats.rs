@@ -0,0 +1,1639 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Distribution summaries of a campaign's benchmark durations, computed in the
+//! database. Responses that were excluded in review are left out.
+use std::collections::BTreeMap;
+
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Uuid;
+
+use super::get_admin_check_login;
+use super::members::{self, CampaignRole};
+use crate::api::v1::bench::SubmissionType;
+use crate::errors::*;
+use crate::AppData;
+
+pub mod routes {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+    pub struct Stats {
+        pub summary: &'static str,
+        pub recommend: &'static str,
+        pub levels: &'static str,
+        pub hashrate: &'static str,
+        pub groups: &'static str,
+    }
+
+    impl Stats {
+        pub const fn new() -> Stats {
+            let summary = "/admin/api/v1/campaign/{uuid}/stats";
+            let recommend = "/admin/api/v1/campaign/{uuid}/stats/recommend";
+            let levels = "/admin/api/v1/campaign/{uuid}/stats/levels";
+            let hashrate = "/admin/api/v1/campaign/{uuid}/stats/hashrate";
+            let groups = "/admin/api/v1/campaign/{uuid}/stats/groups";
+            Stats {
+                summary,
+                recommend,
+                levels,
+                hashrate,
+                groups,
+            }
+        }
+
+        pub fn get_summary_route(&self, campaign_id: &str) -> String {
+            self.summary.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_recommend_route(&self, campaign_id: &str) -> String {
+            self.recommend.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_levels_route(&self, campaign_id: &str) -> String {
+            self.levels.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_hashrate_route(&self, campaign_id: &str) -> String {
+            self.hashrate.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_groups_route(&self, campaign_id: &str) -> String {
+            self.groups.replace("{uuid}", campaign_id)
+        }
+    }
+}
+
+/// duration distribution, in milliseconds, of the benches at a difficulty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyStats {
+    pub difficulty: i32,
+    /// number of benches
+    pub count: i64,
+    pub mean: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmissionTypeStats {
+    pub submission_type: SubmissionType,
+    pub responses: i64,
+    pub first_submitted_at: i64,
+    pub last_submitted_at: i64,
+    /// ordered by difficulty
+    pub difficulties: Vec<DifficultyStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CampaignStats {
+    pub responses: i64,
+    /// `None` when the campaign has no responses
+    pub first_submitted_at: Option<i64>,
+    pub last_submitted_at: Option<i64>,
+    /// ordered by submission type name
+    pub submission_types: Vec<SubmissionTypeStats>,
+    /// ordered by number of responses, devices without an alias last
+    pub devices: Vec<DeviceResponses>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceResponses {
+    /// see [SurveyResponse::canonical_device](super::campaigns::SurveyResponse::canonical_device),
+    /// `None` for devices without an alias
+    pub canonical_device: Option<String>,
+    pub responses: i64,
+}
+
+/// a bench's duration, in milliseconds, at its difficulty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchPoint {
+    pub submission_type: SubmissionType,
+    pub difficulty: i32,
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResponses {
+    /// UNIX timestamp of the day's midnight, in UTC
+    pub day: i64,
+    pub responses: i64,
+}
+
+/// hashrate distribution, in hashes per second, of responses that have a
+/// [HashrateFit](crate::api::v1::bench::HashrateFit)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashrateStats {
+    pub responses: i64,
+    pub mean: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p95: f64,
+    pub min: f64,
+    pub max: f64,
+    /// mean coefficient of determination of the fits
+    pub mean_r2: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceClassHashrate {
+    /// form factor recognised from the user agent, `None` if it wasn't recognised
+    pub device_class: Option<String>,
+    pub hashrate: HashrateStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceHashrate {
+    /// see [SurveyResponse::canonical_device](super::campaigns::SurveyResponse::canonical_device)
+    pub canonical_device: String,
+    pub hashrate: HashrateStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmissionTypeHashrate {
+    pub submission_type: SubmissionType,
+    /// all devices
+    pub hashrate: HashrateStats,
+    /// ordered by device class, unrecognised devices last
+    pub device_classes: Vec<DeviceClassHashrate>,
+    /// ordered by canonical device name; devices without an alias are left out
+    pub devices: Vec<DeviceHashrate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CampaignHashrate {
+    /// ordered by submission type name
+    pub submission_types: Vec<SubmissionTypeHashrate>,
+}
+
+/// groups that have fewer responses than this are suppressed, unless
+/// [GroupQuery::min_size] says otherwise
+pub const DEFAULT_MIN_GROUP_SIZE: u32 = 5;
+
+/// dimension that responses are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    SubmissionType,
+    /// thread count, bucketed into 1, 2, 3-4, 5-8, 9-16 and 17+
+    Threads,
+    /// operating system recognised from the user agent
+    Os,
+    /// browser recognised from the user agent
+    Browser,
+    /// see [SurveyResponse::canonical_device](super::campaigns::SurveyResponse::canonical_device)
+    Device,
+}
+
+impl ToString for GroupBy {
+    fn to_string(&self) -> String {
+        let s = serde_json::to_string(&self).unwrap();
+        (&s[1..(s.len() - 1)]).to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupQuery {
+    pub by: GroupBy,
+    /// defaults to [DEFAULT_MIN_GROUP_SIZE]
+    pub min_size: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupStats {
+    /// `None` for responses whose value of the dimension is unknown
+    pub group: Option<String>,
+    pub responses: i64,
+    /// ordered by difficulty
+    pub difficulties: Vec<DifficultyStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CampaignGroups {
+    pub by: GroupBy,
+    pub min_size: u32,
+    /// ordered by number of responses, largest group first
+    pub groups: Vec<GroupStats>,
+    /// responses in groups smaller than `min_size`
+    pub suppressed: i64,
+}
+
+/// (De)serializes a time budget given in seconds as milliseconds, the unit of bench
+/// durations
+pub mod seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn to_millis(seconds: f64) -> f64 {
+        seconds * 1000.0
+    }
+
+    pub fn serialize<S: Serializer>(millis: &f64, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_f64(millis / 1000.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<f64, D::Error> {
+        f64::deserialize(d).map(to_millis)
+    }
+}
+
+/// "`percentile`% of devices solve within `max_duration`"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecommendationTarget {
+    pub percentile: f64,
+    /// milliseconds; seconds in requests
+    #[serde(with = "seconds")]
+    pub max_duration: f64,
+    /// only consider responses of this submission type
+    pub bench_type: Option<SubmissionType>,
+}
+
+impl RecommendationTarget {
+    pub fn validate(&self) -> ServiceResult<()> {
+        if !(self.percentile > 0.0 && self.percentile <= 100.0)
+            || !(self.max_duration > 0.0 && self.max_duration.is_finite())
+        {
+            Err(ServiceError::InvalidRecommendationTarget)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// duration at [RecommendationTarget::percentile] of a measured difficulty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PercentileDuration {
+    pub difficulty: i32,
+    /// milliseconds, like bench durations
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recommendation {
+    pub difficulty_factor: u32,
+    /// share of responses, between 0 and 1, expected to take longer than
+    /// [RecommendationTarget::max_duration] at `difficulty_factor`
+    pub exceeding: f64,
+    /// the target is met at every measured difficulty, so `difficulty_factor` is
+    /// the highest one rather than an interpolation
+    pub capped: bool,
+    pub responses: usize,
+    pub measured: Vec<PercentileDuration>,
+}
+
+/// `p`-th percentile (0 to 100) of `sorted`, interpolated like Postgres'
+/// `PERCENTILE_CONT`
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (sorted.len() - 1) as f64 * p / 100.0;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Duration at `difficulty` on a response's (difficulty, duration) curve, which
+/// starts at the origin. Beyond the last measured difficulty, the last segment is
+/// extended.
+fn duration_at(points: &[(f64, f64)], difficulty: f64) -> f64 {
+    let mut prev = (0.0, 0.0);
+    for (i, &(d, t)) in points.iter().enumerate() {
+        if difficulty <= d || i == points.len() - 1 {
+            if d == prev.0 {
+                return t;
+            }
+            return prev.1 + (t - prev.1) * (difficulty - prev.0) / (d - prev.0);
+        }
+        prev = (d, t);
+    }
+    0.0
+}
+
+/// Recommend a difficulty factor for `target` from per-response curves of
+/// (difficulty, mean duration in milliseconds), each ordered by difficulty. `None`
+/// when there aren't any.
+fn recommend_difficulty(
+    responses: &[Vec<(f64, f64)>],
+    target: &RecommendationTarget,
+) -> Option<Recommendation> {
+    let mut by_difficulty: BTreeMap<i32, Vec<f64>> = BTreeMap::new();
+    for r in responses.iter() {
+        for &(d, t) in r.iter() {
+            by_difficulty.entry(d as i32).or_default().push(t);
+        }
+    }
+    if by_difficulty.is_empty() {
+        return None;
+    }
+
+    let measured: Vec<PercentileDuration> = by_difficulty
+        .into_iter()
+        .map(|(difficulty, mut durations)| {
+            durations.sort_by(f64::total_cmp);
+            PercentileDuration {
+                difficulty,
+                duration: percentile(&durations, target.percentile),
+            }
+        })
+        .collect();
+
+    let max_duration = target.max_duration;
+
+    // first measured difficulty that misses the target bounds the recommendation
+    let mut prev = (0.0, 0.0);
+    let mut interpolated = None;
+    for m in measured.iter() {
+        let (d, t) = (m.difficulty as f64, m.duration);
+        if t > max_duration {
+            interpolated =
+                Some(prev.0 + (max_duration - prev.1) * (d - prev.0) / (t - prev.1));
+            break;
+        }
+        prev = (d, t);
+    }
+    let capped = interpolated.is_none();
+    let difficulty = interpolated.unwrap_or(prev.0).floor().max(1.0);
+
+    let exceeding = responses
+        .iter()
+        .filter(|r| !r.is_empty() && duration_at(r, difficulty) > max_duration)
+        .count();
+    let with_benches = responses.iter().filter(|r| !r.is_empty()).count();
+
+    Some(Recommendation {
+        difficulty_factor: difficulty as u32,
+        exceeding: exceeding as f64 / with_benches as f64,
+        capped,
+        responses: with_benches,
+        measured,
+    })
+}
+
+/// upper bound on the number of levels in a [LevelsTarget]
+pub const MAX_LEVELS: usize = 20;
+
+/// mCaptcha's sitekey level: once a site sees more than `visitor_threshold`
+/// visitors, challenges are issued at `difficulty_factor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Level {
+    pub visitor_threshold: u32,
+    pub difficulty_factor: u32,
+}
+
+/// levels definition, as accepted by mCaptcha's sitekey configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelsConfig {
+    pub levels: Vec<Level>,
+}
+
+/// time budget for visitors once traffic crosses `visitor_threshold`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelBudget {
+    pub visitor_threshold: u32,
+    /// milliseconds; seconds in requests
+    #[serde(with = "seconds")]
+    pub max_duration: f64,
+}
+
+/// "`percentile`% of devices solve within each budget's `max_duration`"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelsTarget {
+    pub percentile: f64,
+    pub bench_type: Option<SubmissionType>,
+    pub budgets: Vec<LevelBudget>,
+}
+
+impl LevelsTarget {
+    fn level_target(&self, budget: &LevelBudget) -> RecommendationTarget {
+        RecommendationTarget {
+            percentile: self.percentile,
+            max_duration: budget.max_duration,
+            bench_type: self.bench_type.clone(),
+        }
+    }
+
+    pub fn validate(&self) -> ServiceResult<()> {
+        if self.budgets.is_empty() || self.budgets.len() > MAX_LEVELS {
+            return Err(ServiceError::InvalidLevels);
+        }
+        for (i, budget) in self.budgets.iter().enumerate() {
+            self.level_target(budget).validate()?;
+            if i > 0 && budget.visitor_threshold <= self.budgets[i - 1].visitor_threshold
+            {
+                return Err(ServiceError::InvalidLevels);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Levels for `target`'s budgets. mCaptcha expects difficulty factors to rise with
+/// visitor thresholds, so a level that would not is set one above the previous
+/// level.
+fn levels_config(
+    responses: &[Vec<(f64, f64)>],
+    target: &LevelsTarget,
+) -> Option<LevelsConfig> {
+    let mut levels: Vec<Level> = Vec::with_capacity(target.budgets.len());
+    for budget in target.budgets.iter() {
+        let recommendation =
+            recommend_difficulty(responses, &target.level_target(budget))?;
+        let difficulty_factor = match levels.last() {
+            Some(prev) if prev.difficulty_factor >= recommendation.difficulty_factor => {
+                prev.difficulty_factor + 1
+            }
+            _ => recommendation.difficulty_factor,
+        };
+        levels.push(Level {
+            visitor_threshold: budget.visitor_threshold,
+            difficulty_factor,
+        });
+    }
+    Some(LevelsConfig { levels })
+}
+
+pub mod runners {
+    use super::*;
+
+    /// per submission type and difficulty duration summaries of campaign `uuid`
+    pub async fn summary(uuid: &Uuid, data: &AppData) -> ServiceResult<CampaignStats> {
+        struct InnerTotals {
+            name: String,
+            responses: i64,
+            first_submitted_at: OffsetDateTime,
+            last_submitted_at: OffsetDateTime,
+        }
+
+        struct InnerDifficulty {
+            name: String,
+            difficulty: i32,
+            count: i64,
+            mean: f64,
+            p5: f64,
+            p25: f64,
+            median: f64,
+            p75: f64,
+            p90: f64,
+            p95: f64,
+            p99: f64,
+            min: f32,
+            max: f32,
+        }
+
+        let totals = sqlx::query_as!(
+            InnerTotals,
+            r#"SELECT
+                survey_bench_type.name,
+                COUNT(*) AS "responses!",
+                MIN(survey_responses.submitted_at) AS "first_submitted_at!",
+                MAX(survey_responses.submitted_at) AS "last_submitted_at!"
+            FROM
+                survey_responses
+            INNER JOIN survey_bench_type ON
+                survey_bench_type.ID = survey_responses.submission_bench_type_id
+            WHERE
+                survey_responses.campaign_id = $1
+            AND
+                survey_responses.review_state_id <> (
+                    SELECT ID FROM survey_response_review_state WHERE name = 'excluded'
+                )
+            GROUP BY
+                survey_bench_type.name
+            ORDER BY
+                survey_bench_type.name"#,
+            uuid,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        let mut difficulties = sqlx::query_as!(
+            InnerDifficulty,
+            r#"SELECT
+                survey_bench_type.name,
+                survey_benches.difficulty,
+                COUNT(*) AS "count!",
+                AVG(survey_benches.duration) AS "mean!",
+                PERCENTILE_CONT(0.05) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p5!",
+                PERCENTILE_CONT(0.25) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p25!",
+                PERCENTILE_CONT(0.5) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "median!",
+                PERCENTILE_CONT(0.75) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p75!",
+                PERCENTILE_CONT(0.9) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p90!",
+                PERCENTILE_CONT(0.95) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p95!",
+                PERCENTILE_CONT(0.99) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p99!",
+                MIN(survey_benches.duration) AS "min!",
+                MAX(survey_benches.duration) AS "max!"
+            FROM
+                survey_benches
+            INNER JOIN survey_responses ON
+                survey_responses.ID = survey_benches.resp_id
+            INNER JOIN survey_bench_type ON
+                survey_bench_type.ID = survey_responses.submission_bench_type_id
+            WHERE
+                survey_responses.campaign_id = $1
+            AND
+                survey_responses.review_state_id <> (
+                    SELECT ID FROM survey_response_review_state WHERE name = 'excluded'
+                )
+            GROUP BY
+                survey_bench_type.name, survey_benches.difficulty
+            ORDER BY
+                survey_bench_type.name, survey_benches.difficulty"#,
+            uuid,
+        )
+        .fetch_all(&data.db)
+        .await?
+        .into_iter()
+        .peekable();
+
+        let devices = sqlx::query_as!(
+            DeviceResponses,
+            r#"SELECT
+                canonical_device,
+                COUNT(*) AS "responses!"
+            FROM
+                survey_responses
+            WHERE
+                campaign_id = $1
+            AND
+                review_state_id <> (
+                    SELECT ID FROM survey_response_review_state WHERE name = 'excluded'
+                )
+            GROUP BY
+                canonical_device
+            ORDER BY
+                canonical_device IS NULL, COUNT(*) DESC, canonical_device"#,
+            uuid,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        let mut submission_types = Vec::with_capacity(totals.len());
+        for t in totals {
+            let mut type_difficulties = Vec::new();
+            while let Some(d) = difficulties.next_if(|d| d.name == t.name) {
+                type_difficulties.push(DifficultyStats {
+                    difficulty: d.difficulty,
+                    count: d.count,
+                    mean: d.mean,
+                    p5: d.p5,
+                    p25: d.p25,
+                    median: d.median,
+                    p75: d.p75,
+                    p90: d.p90,
+                    p95: d.p95,
+                    p99: d.p99,
+                    min: d.min,
+                    max: d.max,
+                });
+            }
+            submission_types.push(SubmissionTypeStats {
+                submission_type: SubmissionType::new(t.name),
+                responses: t.responses,
+                first_submitted_at: t.first_submitted_at.unix_timestamp(),
+                last_submitted_at: t.last_submitted_at.unix_timestamp(),
+                difficulties: type_difficulties,
+            });
+        }
+
+        Ok(CampaignStats {
+            responses: submission_types.iter().map(|t| t.responses).sum(),
+            first_submitted_at: submission_types
+                .iter()
+                .map(|t| t.first_submitted_at)
+                .min(),
+            last_submitted_at: submission_types
+                .iter()
+                .map(|t| t.last_submitted_at)
+                .max(),
+            submission_types,
+            devices,
+        })
+    }
+
+    /// hashrate distribution of campaign `uuid`'s responses, per submission type,
+    /// device class and canonical device
+    pub async fn hashrate(
+        uuid: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<CampaignHashrate> {
+        struct InnerHashrate {
+            name: String,
+            device_class: Option<String>,
+            canonical_device: Option<String>,
+            by_class: bool,
+            by_device: bool,
+            responses: i64,
+            mean: f64,
+            p5: f64,
+            p25: f64,
+            median: f64,
+            p75: f64,
+            p95: f64,
+            min: f64,
+            max: f64,
+            mean_r2: f64,
+        }
+
+        // the distribution of all devices comes first, followed by the device
+        // classes and the canonical devices of its submission type
+        let rows = sqlx::query_as!(
+            InnerHashrate,
+            r#"SELECT
+                r.name AS "name!",
+                r.form_factor AS device_class,
+                r.canonical_device,
+                GROUPING(r.form_factor) = 0 AS "by_class!",
+                GROUPING(r.canonical_device) = 0 AS "by_device!",
+                COUNT(*) AS "responses!",
+                AVG(r.hashrate) AS "mean!",
+                PERCENTILE_CONT(0.05) WITHIN GROUP (ORDER BY r.hashrate) AS "p5!",
+                PERCENTILE_CONT(0.25) WITHIN GROUP (ORDER BY r.hashrate) AS "p25!",
+                PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY r.hashrate) AS "median!",
+                PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY r.hashrate) AS "p75!",
+                PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY r.hashrate) AS "p95!",
+                MIN(r.hashrate) AS "min!",
+                MAX(r.hashrate) AS "max!",
+                AVG(r.hashrate_r2) AS "mean_r2!"
+            FROM (
+                SELECT
+                    survey_bench_type.name,
+                    survey_responses.form_factor,
+                    survey_responses.canonical_device,
+                    survey_responses.hashrate,
+                    survey_responses.hashrate_r2
+                FROM
+                    survey_responses
+                INNER JOIN survey_bench_type ON
+                    survey_bench_type.ID = survey_responses.submission_bench_type_id
+                WHERE
+                    survey_responses.campaign_id = $1
+                AND
+                    survey_responses.review_state_id <> (
+                        SELECT ID FROM survey_response_review_state WHERE name = 'excluded'
+                    )
+                AND
+                    survey_responses.hashrate IS NOT NULL
+            ) AS r
+            GROUP BY GROUPING SETS (
+                (r.name),
+                (r.name, r.form_factor),
+                (r.name, r.canonical_device)
+            )
+            HAVING
+                GROUPING(r.canonical_device) = 1 OR r.canonical_device IS NOT NULL
+            ORDER BY
+                r.name,
+                GROUPING(r.canonical_device, r.form_factor) DESC,
+                r.form_factor NULLS LAST,
+                r.canonical_device"#,
+            uuid,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        let mut submission_types: Vec<SubmissionTypeHashrate> = Vec::new();
+        for r in rows {
+            let hashrate = HashrateStats {
+                responses: r.responses,
+                mean: r.mean,
+                p5: r.p5,
+                p25: r.p25,
+                median: r.median,
+                p75: r.p75,
+                p95: r.p95,
+                min: r.min,
+                max: r.max,
+                mean_r2: r.mean_r2,
+            };
+            if !r.by_class && !r.by_device {
+                submission_types.push(SubmissionTypeHashrate {
+                    submission_type: SubmissionType::new(r.name),
+                    hashrate,
+                    device_classes: Vec::new(),
+                    devices: Vec::new(),
+                });
+            } else if let Some(t) = submission_types.last_mut() {
+                match r.canonical_device {
+                    Some(canonical_device) if r.by_device => {
+                        t.devices.push(DeviceHashrate {
+                            canonical_device,
+                            hashrate,
+                        })
+                    }
+                    _ => t.device_classes.push(DeviceClassHashrate {
+                        device_class: r.device_class,
+                        hashrate,
+                    }),
+                }
+            }
+        }
+        Ok(CampaignHashrate { submission_types })
+    }
+
+    /// benches of campaign `uuid`'s `limit` most recent responses
+    pub async fn bench_points(
+        uuid: &Uuid,
+        limit: i64,
+        data: &AppData,
+    ) -> ServiceResult<Vec<BenchPoint>> {
+        struct InnerBenchPoint {
+            name: String,
+            difficulty: i32,
+            duration: f32,
+        }
+
+        let points = sqlx::query_as!(
+            InnerBenchPoint,
+            r#"SELECT
+                survey_bench_type.name,
+                survey_benches.difficulty,
+                survey_benches.duration
+            FROM
+                survey_benches
+            INNER JOIN survey_responses ON
+                survey_responses.ID = survey_benches.resp_id
+            INNER JOIN survey_bench_type ON
+                survey_bench_type.ID = survey_responses.submission_bench_type_id
+            WHERE
+                survey_responses.ID IN (
+                    SELECT ID FROM survey_responses
+                    WHERE
+                        campaign_id = $1
+                    AND
+                        review_state_id <> (
+                            SELECT ID FROM survey_response_review_state
+                            WHERE name = 'excluded'
+                        )
+                    ORDER BY ID DESC
+                    LIMIT $2
+                )
+            ORDER BY
+                survey_responses.ID, survey_benches.difficulty"#,
+            uuid,
+            limit,
+        )
+        .fetch_all(&data.db)
+        .await?
+        .into_iter()
+        .map(|p| BenchPoint {
+            submission_type: SubmissionType::new(p.name),
+            difficulty: p.difficulty,
+            duration: p.duration,
+        })
+        .collect();
+        Ok(points)
+    }
+
+    /// number of campaign `uuid`'s responses per UTC day, ordered by day. Days
+    /// without responses are left out.
+    pub async fn daily_responses(
+        uuid: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<Vec<DailyResponses>> {
+        struct InnerDay {
+            day: OffsetDateTime,
+            responses: i64,
+        }
+
+        let days = sqlx::query_as!(
+            InnerDay,
+            r#"SELECT
+                date_trunc('day', survey_responses.submitted_at, 'UTC') AS "day!",
+                COUNT(*) AS "responses!"
+            FROM
+                survey_responses
+            WHERE
+                survey_responses.campaign_id = $1
+            AND
+                survey_responses.review_state_id <> (
+                    SELECT ID FROM survey_response_review_state WHERE name = 'excluded'
+                )
+            GROUP BY
+                1
+            ORDER BY
+                1"#,
+            uuid,
+        )
+        .fetch_all(&data.db)
+        .await?
+        .into_iter()
+        .map(|d| DailyResponses {
+            day: d.day.unix_timestamp(),
+            responses: d.responses,
+        })
+        .collect();
+        Ok(days)
+    }
+
+    /// per group and difficulty duration summaries of campaign `uuid`, with
+    /// responses grouped by [GroupQuery::by]
+    pub async fn groups(
+        uuid: &Uuid,
+        query: &GroupQuery,
+        data: &AppData,
+    ) -> ServiceResult<CampaignGroups> {
+        struct InnerGroupDifficulty {
+            group_key: Option<String>,
+            responses: i64,
+            difficulty: i32,
+            count: i64,
+            mean: f64,
+            p5: f64,
+            p25: f64,
+            median: f64,
+            p75: f64,
+            p90: f64,
+            p95: f64,
+            p99: f64,
+            min: f32,
+            max: f32,
+        }
+
+        let min_size = query.min_size.unwrap_or(DEFAULT_MIN_GROUP_SIZE);
+
+        let rows = sqlx::query_as!(
+            InnerGroupDifficulty,
+            r#"WITH r AS (
+                SELECT
+                    survey_responses.ID,
+                    CASE $2::TEXT
+                        WHEN 'submission_type' THEN survey_bench_type.name
+                        WHEN 'threads' THEN CASE
+                            WHEN survey_responses.threads IS NULL THEN NULL
+                            WHEN survey_responses.threads <= 1 THEN '1'
+                            WHEN survey_responses.threads = 2 THEN '2'
+                            WHEN survey_responses.threads <= 4 THEN '3-4'
+                            WHEN survey_responses.threads <= 8 THEN '5-8'
+                            WHEN survey_responses.threads <= 16 THEN '9-16'
+                            ELSE '17+'
+                        END
+                        WHEN 'os' THEN survey_responses.os
+                        WHEN 'browser' THEN survey_responses.browser
+                        WHEN 'device' THEN
+                            survey_responses.canonical_device
+                    END AS group_key
+                FROM
+                    survey_responses
+                INNER JOIN survey_bench_type ON
+                    survey_bench_type.ID = survey_responses.submission_bench_type_id
+                WHERE
+                    survey_responses.campaign_id = $1
+                AND
+                    survey_responses.review_state_id <> (
+                        SELECT ID FROM survey_response_review_state WHERE name = 'excluded'
+                    )
+            ),
+            sizes AS (
+                SELECT group_key, COUNT(*) AS responses FROM r GROUP BY group_key
+            )
+            SELECT
+                sizes.group_key,
+                sizes.responses AS "responses!",
+                survey_benches.difficulty,
+                COUNT(*) AS "count!",
+                AVG(survey_benches.duration) AS "mean!",
+                PERCENTILE_CONT(0.05) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p5!",
+                PERCENTILE_CONT(0.25) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p25!",
+                PERCENTILE_CONT(0.5) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "median!",
+                PERCENTILE_CONT(0.75) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p75!",
+                PERCENTILE_CONT(0.9) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p90!",
+                PERCENTILE_CONT(0.95) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p95!",
+                PERCENTILE_CONT(0.99) WITHIN GROUP
+                    (ORDER BY survey_benches.duration) AS "p99!",
+                MIN(survey_benches.duration) AS "min!",
+                MAX(survey_benches.duration) AS "max!"
+            FROM
+                sizes
+            INNER JOIN r ON
+                r.group_key IS NOT DISTINCT FROM sizes.group_key
+            INNER JOIN survey_benches ON
+                survey_benches.resp_id = r.ID
+            GROUP BY
+                sizes.group_key, sizes.responses, survey_benches.difficulty
+            ORDER BY
+                sizes.responses DESC,
+                sizes.group_key NULLS LAST,
+                survey_benches.difficulty"#,
+            uuid,
+            query.by.to_string(),
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        let mut groups: Vec<GroupStats> = Vec::new();
+        for r in rows {
+            let difficulty = DifficultyStats {
+                difficulty: r.difficulty,
+                count: r.count,
+                mean: r.mean,
+                p5: r.p5,
+                p25: r.p25,
+                median: r.median,
+                p75: r.p75,
+                p90: r.p90,
+                p95: r.p95,
+                p99: r.p99,
+                min: r.min,
+                max: r.max,
+            };
+            match groups.last_mut() {
+                Some(g) if g.group == r.group_key => g.difficulties.push(difficulty),
+                _ => groups.push(GroupStats {
+                    group: r.group_key,
+                    responses: r.responses,
+                    difficulties: vec![difficulty],
+                }),
+            }
+        }
+
+        let suppressed = groups
+            .iter()
+            .filter(|g| g.responses < min_size as i64)
+            .map(|g| g.responses)
+            .sum();
+        groups.retain(|g| g.responses >= min_size as i64);
+
+        Ok(CampaignGroups {
+            by: query.by,
+            min_size,
+            groups,
+            suppressed,
+        })
+    }
+
+    /// (difficulty, mean duration in milliseconds) curve of each of campaign
+    /// `uuid`'s responses, ordered by difficulty
+    async fn response_curves(
+        uuid: &Uuid,
+        bench_type: &Option<SubmissionType>,
+        data: &AppData,
+    ) -> ServiceResult<Vec<Vec<(f64, f64)>>> {
+        struct InnerDuration {
+            resp_id: i32,
+            difficulty: i32,
+            duration: f64,
+        }
+
+        let durations = sqlx::query_as!(
+            InnerDuration,
+            r#"SELECT
+                survey_benches.resp_id,
+                survey_benches.difficulty,
+                AVG(survey_benches.duration) AS "duration!"
+            FROM
+                survey_benches
+            INNER JOIN survey_responses ON
+                survey_responses.ID = survey_benches.resp_id
+            INNER JOIN survey_bench_type ON
+                survey_bench_type.ID = survey_responses.submission_bench_type_id
+            WHERE
+                survey_responses.campaign_id = $1
+            AND
+                survey_responses.review_state_id <> (
+                    SELECT ID FROM survey_response_review_state WHERE name = 'excluded'
+                )
+            AND
+                ($2::TEXT IS NULL OR survey_bench_type.name = $2)
+            GROUP BY
+                survey_benches.resp_id, survey_benches.difficulty
+            ORDER BY
+                survey_benches.resp_id, survey_benches.difficulty"#,
+            uuid,
+            bench_type.as_ref().map(|t| t.to_string()),
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        let mut responses: Vec<Vec<(f64, f64)>> = Vec::new();
+        let mut resp_id = None;
+        for d in durations {
+            if resp_id != Some(d.resp_id) {
+                resp_id = Some(d.resp_id);
+                responses.push(Vec::new());
+            }
+            responses
+                .last_mut()
+                .unwrap()
+                .push((d.difficulty as f64, d.duration));
+        }
+        Ok(responses)
+    }
+
+    /// recommended difficulty factor for `target`, from campaign `uuid`'s benches
+    pub async fn recommend(
+        uuid: &Uuid,
+        target: &RecommendationTarget,
+        data: &AppData,
+    ) -> ServiceResult<Recommendation> {
+        target.validate()?;
+        let responses = response_curves(uuid, &target.bench_type, data).await?;
+        super::recommend_difficulty(&responses, target)
+            .ok_or(ServiceError::CampaignHasNoResults)
+    }
+
+    /// mCaptcha levels that meet `target`'s time budgets, from campaign `uuid`'s
+    /// benches
+    pub async fn levels(
+        uuid: &Uuid,
+        target: &LevelsTarget,
+        data: &AppData,
+    ) -> ServiceResult<LevelsConfig> {
+        target.validate()?;
+        let responses = response_curves(uuid, &target.bench_type, data).await?;
+        super::levels_config(&responses, target)
+            .ok_or(ServiceError::CampaignHasNoResults)
+    }
+}
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(summary);
+    cfg.service(recommend);
+    cfg.service(levels);
+    cfg.service(hashrate);
+    cfg.service(groups);
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.stats.summary",
+    wrap = "get_admin_check_login()"
+)]
+async fn summary(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    members::runners::require_role(&username, &uuid, CampaignRole::Viewer, &data)
+        .await?;
+    let stats = runners::summary(&uuid, &data).await?;
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.stats.recommend",
+    wrap = "get_admin_check_login()"
+)]
+async fn recommend(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    query: web::Query<RecommendationTarget>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    members::runners::require_role(&username, &uuid, CampaignRole::Viewer, &data)
+        .await?;
+    let recommendation = runners::recommend(&uuid, &query, &data).await?;
+    Ok(HttpResponse::Ok().json(recommendation))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.stats.levels",
+    wrap = "get_admin_check_login()"
+)]
+async fn levels(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    payload: web::Json<LevelsTarget>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    members::runners::require_role(&username, &uuid, CampaignRole::Viewer, &data)
+        .await?;
+    let levels = runners::levels(&uuid, &payload, &data).await?;
+    Ok(HttpResponse::Ok().json(levels))
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.stats.hashrate",
+    wrap = "get_admin_check_login()"
+)]
+async fn hashrate(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    members::runners::require_role(&username, &uuid, CampaignRole::Viewer, &data)
+        .await?;
+    let hashrate = runners::hashrate(&uuid, &data).await?;
+    Ok(HttpResponse::Ok().json(hashrate))
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.stats.groups",
+    wrap = "get_admin_check_login()"
+)]
+async fn groups(
+    id: Identity,
+    data: AppData,
+    path: web::Path<String>,
+    query: web::Query<GroupQuery>,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    members::runners::require_role(&username, &uuid, CampaignRole::Viewer, &data)
+        .await?;
+    let groups = runners::groups(&uuid, &query, &data).await?;
+    Ok(HttpResponse::Ok().json(groups))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use actix_web::ResponseError;
+
+    use super::*;
+    use crate::api::v1::bench::{Bench, Submission};
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+
+    #[test]
+    fn recommendation_works() {
+        let responses = vec![
+            vec![(1.0, 100.0), (10.0, 1000.0), (100.0, 10000.0)],
+            vec![(1.0, 200.0), (10.0, 2000.0), (100.0, 20000.0)],
+        ];
+        let target = |percentile, max_duration| RecommendationTarget {
+            percentile,
+            max_duration,
+            bench_type: None,
+        };
+
+        assert!((percentile(&[1.0, 2.0], 90.0) - 1.9).abs() < 1e-9);
+        assert_eq!(percentile(&[1.0], 95.0), 1.0);
+        assert_eq!(duration_at(&responses[0], 55.0), 5500.0);
+        assert_eq!(duration_at(&responses[0], 200.0), 20000.0);
+
+        // medians are 0.15s, 1.5s and 15s: interpolated between 10 and 100
+        let r = recommend_difficulty(&responses, &target(50.0, 2000.0)).unwrap();
+        assert_eq!(r.difficulty_factor, 13);
+        assert_eq!(r.exceeding, 0.5);
+        assert!(!r.capped);
+        assert_eq!(r.measured.len(), 3);
+        assert_eq!(r.measured[1].difficulty, 10);
+        assert!((r.measured[1].duration - 1500.0).abs() < 1e-9);
+
+        let r = recommend_difficulty(&responses, &target(95.0, 100_000.0)).unwrap();
+        assert_eq!(r.difficulty_factor, 100);
+        assert_eq!(r.exceeding, 0.0);
+        assert!(r.capped);
+
+        // even the lowest measured difficulty is too slow
+        let r = recommend_difficulty(&responses, &target(95.0, 50.0)).unwrap();
+        assert_eq!(r.difficulty_factor, 1);
+        assert_eq!(r.exceeding, 1.0);
+
+        assert!(recommend_difficulty(&[], &target(95.0, 2000.0)).is_none());
+
+        // budgets are given in seconds
+        let t: RecommendationTarget =
+            serde_json::from_str(r#"{"percentile":95,"max_duration":1.5}"#).unwrap();
+        assert_eq!(t.max_duration, 1500.0);
+        assert_eq!(
+            serde_json::to_value(&t).unwrap()["max_duration"],
+            serde_json::json!(1.5)
+        );
+
+        assert!(target(95.0, 2000.0).validate().is_ok());
+        assert!(target(100.0, 2000.0).validate().is_ok());
+        for t in [
+            target(0.0, 2000.0),
+            target(101.0, 2000.0),
+            target(95.0, 0.0),
+            target(f64::NAN, 2000.0),
+        ] {
+            assert_eq!(t.validate(), Err(ServiceError::InvalidRecommendationTarget));
+        }
+    }
+
+    #[test]
+    fn levels_config_works() {
+        let responses = vec![
+            vec![(1.0, 100.0), (10.0, 1000.0), (100.0, 10000.0)],
+            vec![(1.0, 200.0), (10.0, 2000.0), (100.0, 20000.0)],
+        ];
+        let budget = |visitor_threshold, max_duration| LevelBudget {
+            visitor_threshold,
+            max_duration,
+        };
+        let target = |budgets| LevelsTarget {
+            percentile: 50.0,
+            bench_type: None,
+            budgets,
+        };
+        let level = |visitor_threshold, difficulty_factor| Level {
+            visitor_threshold,
+            difficulty_factor,
+        };
+
+        // the third budget's recommendation is the same as the second's
+        let t = target(vec![
+            budget(10, 100.0),
+            budget(100, 2000.0),
+            budget(1000, 2000.0),
+            budget(5000, 100_000.0),
+        ]);
+        assert!(t.validate().is_ok());
+        assert_eq!(
+            levels_config(&responses, &t).unwrap().levels,
+            vec![
+                level(10, 1),
+                level(100, 13),
+                level(1000, 14),
+                level(5000, 100)
+            ]
+        );
+        assert!(levels_config(&[], &t).is_none());
+
+        assert_eq!(target(vec![]).validate(), Err(ServiceError::InvalidLevels));
+        assert_eq!(
+            target(vec![budget(100, 1000.0), budget(100, 2000.0)]).validate(),
+            Err(ServiceError::InvalidLevels)
+        );
+        assert_eq!(
+            target(vec![budget(10, 1000.0); MAX_LEVELS + 1]).validate(),
+            Err(ServiceError::InvalidLevels)
+        );
+        assert_eq!(
+            target(vec![budget(10, 0.0)]).validate(),
+            Err(ServiceError::InvalidRecommendationTarget)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn campaign_stats_work() {
+        const NAME: &str = "statsuser";
+        const EMAIL: &str = "statsuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let stats_route = V1_API_ROUTES
+            .admin
+            .stats
+            .get_summary_route(&campaign.campaign_id);
+
+        let resp = get_request!(&app, &stats_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let stats: CampaignStats = test::read_body_json(resp).await;
+        assert_eq!(stats.responses, 0);
+        assert!(stats.first_submitted_at.is_none());
+        assert!(stats.submission_types.is_empty());
+
+        let hashrate_route = V1_API_ROUTES
+            .admin
+            .stats
+            .get_hashrate_route(&campaign.campaign_id);
+        let resp = get_request!(&app, &hashrate_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let hashrate: CampaignHashrate = test::read_body_json(resp).await;
+        assert!(hashrate.submission_types.is_empty());
+
+        let recommend_route = V1_API_ROUTES
+            .admin
+            .stats
+            .get_recommend_route(&campaign.campaign_id);
+        let resp = get_request!(
+            &app,
+            &format!("{recommend_route}?percentile=50&max_duration=3"),
+            cookies.clone()
+        );
+        assert_eq!(
+            resp.status(),
+            ServiceError::CampaignHasNoResults.status_code()
+        );
+
+        // wasm is benchmarked at 1x and 2x of SLOW_BENCHES' durations, js at 1x
+        let slower: Vec<Bench> = SLOW_BENCHES
+            .iter()
+            .map(|b| Bench {
+                difficulty: b.difficulty,
+                duration: b.duration * 2.0,
+            })
+            .collect();
+        for (benches, submission_type) in [
+            (SLOW_BENCHES.clone(), SubmissionType::wasm()),
+            (slower, SubmissionType::wasm()),
+            (SLOW_BENCHES.clone(), SubmissionType::js()),
+        ] {
+            let submit_payload = Submission {
+                device_user_provided: "foo".into(),
+                device_software_recognised: "statsuser".into(),
+                threads: 4,
+                benches: solve_benches(
+                    &campaign,
+                    data.clone(),
+                    survey_cookie.clone(),
+                    &benches,
+                )
+                .await,
+                submission_type,
+                idempotency_key: None,
+                hints: DeviceHints::default(),
+            };
+            submit_bench(
+                &submit_payload,
+                &campaign,
+                survey_cookie.clone(),
+                data.clone(),
+            )
+            .await;
+        }
+
+        let resp = get_request!(&app, &stats_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let stats: CampaignStats = test::read_body_json(resp).await;
+        assert_eq!(stats.responses, 3);
+        assert!(stats.first_submitted_at <= stats.last_submitted_at);
+        assert!(stats.first_submitted_at.is_some());
+        assert_eq!(stats.submission_types.len(), 2);
+
+        let js = &stats.submission_types[0];
+        assert_eq!(js.submission_type, SubmissionType::js());
+        assert_eq!(js.responses, 1);
+        assert_eq!(js.difficulties.len(), DIFFICULTIES.len());
+        assert_eq!(js.difficulties[0].median, 1000.0);
+
+        let wasm = &stats.submission_types[1];
+        assert_eq!(wasm.submission_type, SubmissionType::wasm());
+        assert_eq!(wasm.responses, 2);
+        let d = wasm
+            .difficulties
+            .iter()
+            .find(|d| d.difficulty == 5)
+            .unwrap();
+        assert_eq!(d.count, 2);
+        assert_eq!(d.min, 5000.0);
+        assert_eq!(d.max, 10000.0);
+        assert_eq!(d.mean, 7500.0);
+        assert_eq!(d.median, 7500.0);
+        assert!((d.p90 - 9500.0).abs() < 1e-9);
+
+        // median duration at each difficulty equals the difficulty in seconds, so 3
+        // is the highest one within 3s. The slower wasm response misses it.
+        let resp = get_request!(
+            &app,
+            &format!("{recommend_route}?percentile=50&max_duration=3"),
+            cookies.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let recommendation: Recommendation = test::read_body_json(resp).await;
+        assert_eq!(recommendation.difficulty_factor, 3);
+        assert_eq!(recommendation.responses, 3);
+        assert!(!recommendation.capped);
+        assert!((recommendation.exceeding - 1.0 / 3.0).abs() < 1e-9);
+
+        let resp = get_request!(
+            &app,
+            &format!("{recommend_route}?percentile=50&max_duration=3&bench_type=js"),
+            cookies.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let recommendation: Recommendation = test::read_body_json(resp).await;
+        assert_eq!(recommendation.difficulty_factor, 3);
+        assert_eq!(recommendation.responses, 1);
+        assert_eq!(recommendation.exceeding, 0.0);
+
+        let levels_route = V1_API_ROUTES
+            .admin
+            .stats
+            .get_levels_route(&campaign.campaign_id);
+        let levels = LevelsTarget {
+            percentile: 50.0,
+            bench_type: None,
+            budgets: vec![
+                LevelBudget {
+                    visitor_threshold: 50,
+                    max_duration: 3000.0,
+                },
+                LevelBudget {
+                    visitor_threshold: 500,
+                    max_duration: 4500.0,
+                },
+            ],
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&levels, &levels_route)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let config: LevelsConfig = test::read_body_json(resp).await;
+        assert_eq!(
+            config.levels,
+            vec![
+                Level {
+                    visitor_threshold: 50,
+                    difficulty_factor: 3
+                },
+                Level {
+                    visitor_threshold: 500,
+                    difficulty_factor: 4
+                },
+            ]
+        );
+
+        // a second per hash is a hash per second
+        let resp = get_request!(&app, &hashrate_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let hashrate: CampaignHashrate = test::read_body_json(resp).await;
+        assert_eq!(hashrate.submission_types.len(), 2);
+        let js = &hashrate.submission_types[0];
+        assert_eq!(js.submission_type, SubmissionType::js());
+        assert_eq!(js.hashrate.responses, 1);
+        assert!((js.hashrate.median - 1.0).abs() < 1e-6);
+        let wasm = &hashrate.submission_types[1];
+        assert_eq!(wasm.submission_type, SubmissionType::wasm());
+        assert_eq!(wasm.hashrate.responses, 2);
+        assert!((wasm.hashrate.min - 0.5).abs() < 1e-6);
+        assert!((wasm.hashrate.max - 1.0).abs() < 1e-6);
+        assert!((wasm.hashrate.median - 0.75).abs() < 1e-6);
+        assert!((wasm.hashrate.mean_r2 - 1.0).abs() < 1e-9);
+        let responses: i64 = wasm
+            .device_classes
+            .iter()
+            .map(|c| c.hashrate.responses)
+            .sum();
+        assert_eq!(responses, 2);
+
+        let resp = get_request!(
+            &app,
+            &format!("{recommend_route}?percentile=0&max_duration=3"),
+            cookies.clone()
+        );
+        assert_eq!(
+            resp.status(),
+            ServiceError::InvalidRecommendationTarget.status_code()
+        );
+
+        // campaigns that aren't shared with an admin don't exist for them
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES
+                .admin
+                .stats
+                .get_summary_route(&uuid::Uuid::new_v4().to_string()),
+            cookies.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn campaign_groups_work() {
+        const NAME: &str = "statsgroupsuser";
+        const EMAIL: &str = "statsgroupsuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+
+        let slower: Vec<Bench> = BENCHES
+            .iter()
+            .map(|b| Bench {
+                difficulty: b.difficulty,
+                duration: b.duration * 2.0,
+            })
+            .collect();
+        for (benches, threads) in
+            [(BENCHES.clone(), 4), (slower, 3), (BENCHES.clone(), 1)]
+        {
+            let submit_payload = Submission {
+                device_user_provided: "foo".into(),
+                device_software_recognised: NAME.into(),
+                threads,
+                benches: solve_benches(
+                    &campaign,
+                    data.clone(),
+                    survey_cookie.clone(),
+                    &benches,
+                )
+                .await,
+                submission_type: SubmissionType::wasm(),
+                idempotency_key: None,
+                hints: DeviceHints::default(),
+            };
+            submit_bench(
+                &submit_payload,
+                &campaign,
+                survey_cookie.clone(),
+                data.clone(),
+            )
+            .await;
+        }
+
+        let groups_route = V1_API_ROUTES
+            .admin
+            .stats
+            .get_groups_route(&campaign.campaign_id);
+
+        // every group is smaller than the default minimum size
+        let resp =
+            get_request!(&app, &format!("{groups_route}?by=threads"), cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let groups: CampaignGroups = test::read_body_json(resp).await;
+        assert_eq!(groups.min_size, DEFAULT_MIN_GROUP_SIZE);
+        assert!(groups.groups.is_empty());
+        assert_eq!(groups.suppressed, 3);
+
+        let resp = get_request!(
+            &app,
+            &format!("{groups_route}?by=threads&min_size=2"),
+            cookies.clone()
+        );
+        let groups: CampaignGroups = test::read_body_json(resp).await;
+        assert_eq!(groups.by, GroupBy::Threads);
+        assert_eq!(groups.suppressed, 1);
+        assert_eq!(groups.groups.len(), 1);
+        let group = &groups.groups[0];
+        assert_eq!(group.group.as_deref(), Some("3-4"));
+        assert_eq!(group.responses, 2);
+        assert_eq!(group.difficulties.len(), BENCHES.len());
+        for (d, b) in group.difficulties.iter().zip(BENCHES.iter()) {
+            assert_eq!(d.difficulty, b.difficulty);
+            assert_eq!(d.count, 2);
+            assert!((d.median - 1.5 * b.duration as f64).abs() < 1e-6);
+        }
+
+        let resp = get_request!(
+            &app,
+            &format!("{groups_route}?by=submission_type&min_size=1"),
+            cookies.clone()
+        );
+        let groups: CampaignGroups = test::read_body_json(resp).await;
+        assert_eq!(groups.suppressed, 0);
+        assert_eq!(groups.groups.len(), 1);
+        assert_eq!(
+            groups.groups[0].group.as_deref(),
+            Some(SubmissionType::wasm().to_string().as_str())
+        );
+        assert_eq!(groups.groups[0].responses, 3);
+
+        let resp = get_request!(&app, &format!("{groups_route}?by=foo"), cookies);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,345 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Named campaign settings, saved per admin, that the new campaign form can be
+//! pre-filled from
+use actix_identity::Identity;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+
+use super::campaigns::{
+    default_repetitions, validate_difficulties, validate_texts, MAX_REPETITIONS,
+};
+use super::get_admin_check_login;
+use crate::errors::*;
+use crate::AppData;
+
+/// length of `survey_campaign_templates.name`
+pub const MAX_TEMPLATE_NAME_LEN: usize = 100;
+
+pub mod routes {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+    pub struct Templates {
+        pub add: &'static str,
+        pub list: &'static str,
+        pub delete: &'static str,
+    }
+
+    impl Templates {
+        pub const fn new() -> Templates {
+            let add = "/admin/api/v1/templates/add";
+            let list = "/admin/api/v1/templates";
+            let delete = "/admin/api/v1/templates/delete";
+            Templates { add, list, delete }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignTemplate {
+    pub name: String,
+    pub difficulties: Vec<i32>,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: i32,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+impl CampaignTemplate {
+    fn validate(&self) -> ServiceResult<()> {
+        let len = self.name.chars().count();
+        if self.name.trim().is_empty() || len > MAX_TEMPLATE_NAME_LEN {
+            return Err(ServiceError::InvalidTemplateName);
+        }
+        if self.repetitions < 1 || self.repetitions > MAX_REPETITIONS {
+            return Err(ServiceError::InvalidRepetitions);
+        }
+        validate_texts(&self.description, &self.instructions)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateName {
+    pub name: String,
+}
+
+pub mod runners {
+    use super::*;
+
+    /// templates saved by `username`, sorted by name
+    pub async fn list(
+        username: &str,
+        data: &AppData,
+    ) -> ServiceResult<Vec<CampaignTemplate>> {
+        let templates = sqlx::query_as!(
+            CampaignTemplate,
+            "SELECT name, difficulties, repetitions, description, instructions
+            FROM survey_campaign_templates
+            WHERE user_id = (SELECT ID FROM survey_admins WHERE name = $1)
+            ORDER BY name",
+            username,
+        )
+        .fetch_all(&data.db)
+        .await?;
+        Ok(templates)
+    }
+
+    pub async fn get(
+        username: &str,
+        name: &str,
+        data: &AppData,
+    ) -> ServiceResult<CampaignTemplate> {
+        use sqlx::Error::RowNotFound;
+
+        let res = sqlx::query_as!(
+            CampaignTemplate,
+            "SELECT name, difficulties, repetitions, description, instructions
+            FROM survey_campaign_templates
+            WHERE
+                user_id = (SELECT ID FROM survey_admins WHERE name = $1)
+            AND
+                name = $2",
+            username,
+            name,
+        )
+        .fetch_one(&data.db)
+        .await;
+
+        match res {
+            Ok(t) => Ok(t),
+            Err(RowNotFound) => Err(ServiceError::TemplateDoesntExist),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// saves `template`, replacing the admin's template of the same name
+    pub async fn add(
+        username: &str,
+        template: &mut CampaignTemplate,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        template.validate()?;
+        validate_difficulties(&mut template.difficulties)?;
+
+        sqlx::query!(
+            "INSERT INTO survey_campaign_templates
+                (user_id, name, difficulties, repetitions, description, instructions)
+            VALUES (
+                (SELECT ID FROM survey_admins WHERE name = $1),
+                $2, $3, $4, $5, $6
+            )
+            ON CONFLICT (user_id, name) DO UPDATE SET
+                difficulties = EXCLUDED.difficulties,
+                repetitions = EXCLUDED.repetitions,
+                description = EXCLUDED.description,
+                instructions = EXCLUDED.instructions",
+            username,
+            &template.name,
+            &template.difficulties,
+            template.repetitions,
+            template.description.as_ref(),
+            template.instructions.as_ref(),
+        )
+        .execute(&data.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(
+        username: &str,
+        name: &str,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        let res = sqlx::query!(
+            "DELETE FROM survey_campaign_templates
+            WHERE
+                user_id = (SELECT ID FROM survey_admins WHERE name = $1)
+            AND
+                name = $2",
+            username,
+            name,
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::TemplateDoesntExist)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub fn services(cfg: &mut web::ServiceConfig) {
+    cfg.service(list);
+    cfg.service(add);
+    cfg.service(delete);
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "crate::V1_API_ROUTES.admin.templates.list",
+    wrap = "get_admin_check_login()"
+)]
+async fn list(id: Identity, data: AppData) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let templates = runners::list(&username, &data).await?;
+    Ok(HttpResponse::Ok().json(templates))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.templates.add",
+    wrap = "get_admin_check_login()"
+)]
+async fn add(
+    id: Identity,
+    payload: web::Json<CampaignTemplate>,
+    data: AppData,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    let mut payload = payload.into_inner();
+    runners::add(&username, &mut payload, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.admin.templates.delete",
+    wrap = "get_admin_check_login()"
+)]
+async fn delete(
+    id: Identity,
+    payload: web::Json<TemplateName>,
+    data: AppData,
+) -> ServiceResult<impl Responder> {
+    let username = id.identity().unwrap();
+    runners::delete(&username, &payload.name, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+
+    use super::{CampaignTemplate, TemplateName, MAX_TEMPLATE_NAME_LEN};
+    use crate::errors::*;
+    use crate::tests::*;
+    use crate::*;
+
+    #[actix_rt::test]
+    async fn campaign_templates_work() {
+        const NAME: &str = "templatesuser";
+        const EMAIL: &str = "templatesuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+        const TEMPLATE: &str = "ladder";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let app = get_app!(data).await;
+
+        let mut template = CampaignTemplate {
+            name: TEMPLATE.into(),
+            difficulties: vec![3, 1, 2],
+            repetitions: 2,
+            description: Some("foo".into()),
+            instructions: None,
+        };
+        for _ in 0..2 {
+            let resp = test::call_service(
+                &app,
+                post_request!(&template, V1_API_ROUTES.admin.templates.add)
+                    .cookie(cookies.clone())
+                    .to_request(),
+            )
+            .await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            template.repetitions += 1;
+        }
+        // saving under an existing name replaces the template
+        let resp =
+            get_request!(&app, V1_API_ROUTES.admin.templates.list, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let templates: Vec<CampaignTemplate> = test::read_body_json(resp).await;
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].difficulties, vec![1, 2, 3]);
+        assert_eq!(templates[0].repetitions, 3);
+        assert_eq!(templates[0].description, template.description);
+
+        let mut bad = template.clone();
+        bad.name = "a".repeat(MAX_TEMPLATE_NAME_LEN + 1);
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.templates.add,
+            &bad,
+            ServiceError::InvalidTemplateName,
+        )
+        .await;
+        bad.name = TEMPLATE.into();
+        bad.repetitions = 0;
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.templates.add,
+            &bad,
+            ServiceError::InvalidRepetitions,
+        )
+        .await;
+        bad.repetitions = 1;
+        bad.difficulties = vec![1, 1];
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.templates.add,
+            &bad,
+            ServiceError::InvalidDifficulties,
+        )
+        .await;
+
+        let name = TemplateName {
+            name: TEMPLATE.into(),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&name, V1_API_ROUTES.admin.templates.delete)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let templates = super::runners::list(NAME, &AppData::new(data.clone()))
+            .await
+            .unwrap();
+        assert!(templates.is_empty());
+
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.templates.delete,
+            &name,
+            ServiceError::TemplateDoesntExist,
+        )
+        .await;
+    }
+}
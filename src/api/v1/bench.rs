@@ -21,17 +21,33 @@ use actix_auth_middleware::*;
 use actix_session::Session;
 use actix_web::{dev::Payload, HttpRequest};
 use actix_web::{http, web, HttpResponse, Responder};
-use futures::future::try_join_all;
 use serde::{Deserialize, Serialize};
 use sqlx::types::time::OffsetDateTime;
 use sqlx::types::Uuid;
 
+use super::device::{DeviceHints, UserAgent};
+use super::pow;
 use super::{get_uuid, RedirectQuery};
+use crate::api::v1::admin::bench_types;
+use crate::api::v1::admin::campaigns::runners::{
+    check_accepting_responses, lock_accepting_responses,
+};
+use crate::api::v1::admin::campaigns::{
+    CampaignLifecycle, CampaignState, CampaignStatus,
+};
+use crate::api::v1::admin::review::{ReviewState, SanityFlag};
 use crate::errors::*;
 use crate::AppData;
 
 pub const SURVEY_USER_ID: &str = "survey_user_id";
 
+/// length of `device_user_provided` and `device_software_recognised` columns
+pub const MAX_DEVICE_INFO_LEN: usize = 400;
+/// upper bound on `navigator.hardwareConcurrency` that we'll accept
+pub const MAX_THREADS: i32 = 1024;
+/// length of `idempotency_key` column
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 100;
+
 pub mod routes {
     use serde::{Deserialize, Serialize};
 
@@ -42,6 +58,9 @@ pub mod routes {
         pub submit: &'static str,
         pub register: &'static str,
         pub fetch: &'static str,
+        pub info: &'static str,
+        pub receipt: &'static str,
+        pub delete_submission: &'static str,
         pub scope: &'static str,
     }
 
@@ -68,12 +87,18 @@ pub mod routes {
         pub const fn new() -> Benches {
             let submit = "/survey/api/v1/benches/{campaign_id}/submit";
             let fetch = "/survey/api/v1/benches/{campaign_id}/fetch";
+            let info = "/survey/api/v1/benches/{campaign_id}/info";
             let register = "/survey/api/v1/benches/register";
+            let receipt = "/survey/api/v1/benches/receipt";
+            let delete_submission = "/survey/api/v1/benches/receipt/delete";
             let scope = "/survey/api/v1/benches/";
             Benches {
                 submit,
                 register,
                 fetch,
+                info,
+                receipt,
+                delete_submission,
                 scope,
             }
         }
@@ -83,6 +108,9 @@ pub mod routes {
         pub fn fetch_routes(&self, campaign_id: &str) -> String {
             self.fetch.replace("{campaign_id}", campaign_id)
         }
+        pub fn info_route(&self, campaign_id: &str) -> String {
+            self.info.replace("{campaign_id}", campaign_id)
+        }
     }
 }
 
@@ -90,6 +118,9 @@ pub fn services(cfg: &mut web::ServiceConfig) {
     cfg.service(submit);
     cfg.service(register);
     cfg.service(fetch);
+    cfg.service(info);
+    cfg.service(receipt);
+    cfg.service(delete_submission);
 }
 
 pub mod runners {
@@ -125,6 +156,439 @@ pub mod runners {
         }
         Ok(uuid)
     }
+
+    pub async fn get_bench_config(
+        campaign_id: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<BenchConfig> {
+        use sqlx::Error::RowNotFound;
+
+        let res = sqlx::query_as!(
+            BenchConfig,
+            "SELECT
+                difficulties, repetitions, pow_salt AS salt, pow_phrase AS phrase,
+                config_version AS version
+            FROM survey_campaigns WHERE id = $1 AND deleted_at IS NULL;",
+            campaign_id,
+        )
+        .fetch_one(&data.db)
+        .await;
+
+        match res {
+            Ok(config) => Ok(config),
+            Err(RowNotFound) => Err(ServiceError::CampaignDoesntExist),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn get_campaign_info(
+        campaign_id: &Uuid,
+        data: &AppData,
+    ) -> ServiceResult<CampaignInfo> {
+        use sqlx::Error::RowNotFound;
+
+        struct InnerInfo {
+            name: String,
+            description: Option<String>,
+            instructions: Option<String>,
+            difficulties: Vec<i32>,
+            state: String,
+            starts_at: Option<OffsetDateTime>,
+            ends_at: Option<OffsetDateTime>,
+            max_responses: Option<i32>,
+            responses: i64,
+        }
+
+        let res = sqlx::query_as!(
+            InnerInfo,
+            r#"SELECT
+                survey_campaigns.name,
+                survey_campaigns.description,
+                survey_campaigns.instructions,
+                survey_campaigns.difficulties,
+                survey_campaign_state.name AS state,
+                survey_campaigns.starts_at,
+                survey_campaigns.ends_at,
+                survey_campaigns.max_responses,
+                (
+                    SELECT COUNT(*) FROM survey_responses
+                    WHERE campaign_id = $1
+                ) AS "responses!"
+            FROM
+                survey_campaigns
+            INNER JOIN survey_campaign_state ON
+                survey_campaigns.state_id = survey_campaign_state.ID
+            WHERE
+                survey_campaigns.ID = $1
+            AND
+                survey_campaigns.deleted_at IS NULL"#,
+            campaign_id,
+        )
+        .fetch_one(&data.db)
+        .await;
+
+        let i = match res {
+            Ok(i) => i,
+            Err(RowNotFound) => return Err(ServiceError::CampaignDoesntExist),
+            Err(e) => return Err(e.into()),
+        };
+
+        let lifecycle = CampaignLifecycle {
+            state: CampaignState::from_str(&i.state).unwrap(),
+            starts_at: i.starts_at.map(|t| t.unix_timestamp()),
+            ends_at: i.ends_at.map(|t| t.unix_timestamp()),
+            max_responses: i.max_responses,
+            max_responses_per_participant: None,
+        };
+        let status =
+            lifecycle.status(OffsetDateTime::now_utc().unix_timestamp(), i.responses);
+
+        Ok(CampaignInfo {
+            name: i.name,
+            description: i.description,
+            instructions: i.instructions,
+            status,
+            difficulties: i.difficulties,
+        })
+    }
+
+    /// persists a submission and its benches in a single transaction. Returns the ID of
+    /// the proof token issued for the submission. `config_version` is the version of
+    /// the campaign configuration that the submission was validated against.
+    pub async fn submit_runner(
+        user_id: &Uuid,
+        campaign_id: &Uuid,
+        payload: &Submission,
+        config_version: i32,
+        data: &AppData,
+    ) -> ServiceResult<Uuid> {
+        let now = OffsetDateTime::now_utc();
+        let ua = UserAgent::parse(&payload.device_software_recognised);
+        let benches: Vec<Bench> =
+            payload.benches.iter().cloned().map(Bench::from).collect();
+        let fit = HashrateFit::fit(&benches);
+        let flags = SanityFlag::check(&data.settings.sanity, &benches);
+        let review_state = if flags.is_empty() {
+            ReviewState::Accepted
+        } else {
+            ReviewState::Flagged
+        };
+        let flags: Vec<String> = flags.iter().map(|f| f.to_string()).collect();
+        let mut tx = data.db.begin().await?;
+        if let Err(e) = lock_accepting_responses(campaign_id, user_id, &mut tx).await {
+            // a concurrent replay of this submission may have used up the last response
+            if let Some(key) = &payload.idempotency_key {
+                if let Some(submission_id) =
+                    get_idempotent_submission(user_id, campaign_id, key, data).await?
+                {
+                    return Ok(submission_id);
+                }
+            }
+            return Err(e);
+        }
+
+        struct ID {
+            id: i32,
+        }
+        let resp_id = sqlx::query_as!(
+            ID,
+            "INSERT INTO survey_responses (
+                    user_id,
+                    campaign_id,
+                    device_user_provided,
+                    canonical_device,
+                    device_software_recognised,
+                    threads,
+                    submitted_at,
+                    submission_bench_type_id,
+                    idempotency_key,
+                    os,
+                    os_version,
+                    browser,
+                    browser_version,
+                    form_factor,
+                    device_memory,
+                    platform,
+                    mobile,
+                    config_version,
+                    hashrate,
+                    hashrate_r2,
+                    review_state_id,
+                    flags
+                ) VALUES (
+                    $1, $2, $3, survey_canonical_device($3::VARCHAR), $4, $5, $6,
+                    (SELECT ID FROM survey_bench_type WHERE name = $7),
+                    $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19,
+                    (SELECT ID FROM survey_response_review_state WHERE name = $20),
+                    $21
+                )
+        ON CONFLICT (campaign_id, user_id, idempotency_key) DO NOTHING
+        RETURNING ID;",
+            user_id,
+            campaign_id,
+            &payload.device_user_provided,
+            &payload.device_software_recognised,
+            &payload.threads,
+            &now,
+            &payload.submission_type.to_string(),
+            payload.idempotency_key.as_ref(),
+            ua.os,
+            ua.os_version,
+            ua.browser,
+            ua.browser_version,
+            ua.form_factor,
+            payload.hints.memory,
+            payload.hints.platform.as_ref(),
+            payload.hints.mobile,
+            config_version,
+            fit.as_ref().map(|f| f.hashrate),
+            fit.as_ref().map(|f| f.r2),
+            review_state.to_string(),
+            &flags,
+        )
+        .fetch_optional(&mut tx)
+        .await?;
+
+        let resp_id = match resp_id {
+            Some(resp_id) => resp_id,
+            None => {
+                // lost a race against a replay of the same submission
+                tx.rollback().await?;
+                let key = payload.idempotency_key.as_ref().unwrap();
+                return get_idempotent_submission(user_id, campaign_id, key, data)
+                    .await?
+                    .ok_or(ServiceError::InternalServerError);
+            }
+        };
+
+        let mut difficulties = Vec::with_capacity(payload.benches.len());
+        let mut durations = Vec::with_capacity(payload.benches.len());
+        let mut samples = Vec::with_capacity(payload.benches.len());
+        for bench in payload.benches.iter() {
+            let sample = difficulties
+                .iter()
+                .filter(|d| **d == bench.difficulty)
+                .count() as i32;
+            difficulties.push(bench.difficulty);
+            durations.push(bench.duration);
+            samples.push(sample);
+        }
+
+        sqlx::query!(
+            "INSERT INTO survey_benches
+                (resp_id, difficulty, duration, sample)
+            SELECT
+                $1, difficulty, duration, sample
+            FROM
+                UNNEST($2::INTEGER[], $3::REAL[], $4::INTEGER[])
+                    AS b(difficulty, duration, sample);",
+            &resp_id.id,
+            &difficulties,
+            &durations,
+            &samples
+        )
+        .execute(&mut tx)
+        .await?;
+
+        let mut submission_id;
+        loop {
+            submission_id = get_uuid();
+
+            // a failed statement aborts the transaction, so UUID collisions are
+            // detected through the affected row count instead of a constraint error
+            let res = sqlx::query!(
+                "INSERT INTO survey_response_tokens
+                (resp_id, user_id, id)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (id) DO NOTHING;",
+                &resp_id.id,
+                user_id,
+                &submission_id
+            )
+            .execute(&mut tx)
+            .await?;
+
+            if res.rows_affected() == 1 {
+                break;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(submission_id)
+    }
+
+    /// ID of the proof token issued for a submission that was made with
+    /// `idempotency_key`
+    pub async fn get_idempotent_submission(
+        user_id: &Uuid,
+        campaign_id: &Uuid,
+        idempotency_key: &str,
+        data: &AppData,
+    ) -> ServiceResult<Option<Uuid>> {
+        struct Token {
+            id: Uuid,
+        }
+
+        let res = sqlx::query_as!(
+            Token,
+            "SELECT
+                survey_response_tokens.ID
+            FROM
+                survey_response_tokens
+            INNER JOIN survey_responses ON
+                survey_response_tokens.resp_id = survey_responses.ID
+            WHERE
+                survey_responses.campaign_id = $1
+            AND
+                survey_responses.user_id = $2
+            AND
+                survey_responses.idempotency_key = $3",
+            campaign_id,
+            user_id,
+            idempotency_key,
+        )
+        .fetch_optional(&data.db)
+        .await?;
+
+        Ok(res.map(|t| t.id))
+    }
+
+    /// fetch everything that was stored for the submission identified by `proof`
+    pub async fn get_receipt(
+        proof: &SubmissionProof,
+        data: &AppData,
+    ) -> ServiceResult<SubmissionReceipt> {
+        use sqlx::Error::RowNotFound;
+
+        let (user_id, proof_id) = proof.parse()?;
+
+        struct InnerReceipt {
+            id: i32,
+            campaign_id: Uuid,
+            device_user_provided: String,
+            device_software_recognised: String,
+            threads: Option<i32>,
+            submitted_at: OffsetDateTime,
+            name: String,
+            os: Option<String>,
+            os_version: Option<String>,
+            browser: Option<String>,
+            browser_version: Option<String>,
+            form_factor: Option<String>,
+            device_memory: Option<f32>,
+            platform: Option<String>,
+            mobile: Option<bool>,
+        }
+
+        let res = sqlx::query_as!(
+            InnerReceipt,
+            "SELECT
+                survey_responses.ID,
+                survey_responses.campaign_id,
+                survey_responses.device_user_provided,
+                survey_responses.device_software_recognised,
+                survey_responses.threads,
+                survey_responses.submitted_at,
+                survey_bench_type.name,
+                survey_responses.os,
+                survey_responses.os_version,
+                survey_responses.browser,
+                survey_responses.browser_version,
+                survey_responses.form_factor,
+                survey_responses.device_memory,
+                survey_responses.platform,
+                survey_responses.mobile
+            FROM
+                survey_responses
+            INNER JOIN survey_bench_type ON
+                survey_responses.submission_bench_type_id = survey_bench_type.ID
+            INNER JOIN survey_response_tokens ON
+                survey_response_tokens.resp_id = survey_responses.ID
+            WHERE
+                survey_response_tokens.ID = $1
+            AND
+                survey_response_tokens.user_id = $2",
+            &proof_id,
+            &user_id,
+        )
+        .fetch_one(&data.db)
+        .await;
+
+        let r = match res {
+            Ok(r) => r,
+            Err(RowNotFound) => return Err(ServiceError::SubmissionNotFound),
+            Err(e) => return Err(e.into()),
+        };
+
+        let benches = sqlx::query_as!(
+            Bench,
+            "SELECT
+                duration,
+                difficulty
+            FROM
+                survey_benches
+            WHERE
+                resp_id = $1
+            ORDER BY
+                difficulty, sample",
+            r.id,
+        )
+        .fetch_all(&data.db)
+        .await?;
+
+        Ok(SubmissionReceipt {
+            campaign_id: r.campaign_id.to_string(),
+            device_user_provided: r.device_user_provided,
+            device_software_recognised: r.device_software_recognised,
+            threads: r.threads.map(|t| t as usize),
+            submitted_at: r.submitted_at.unix_timestamp(),
+            submission_type: SubmissionType::new(r.name),
+            user_agent: UserAgent {
+                os: r.os,
+                os_version: r.os_version,
+                browser: r.browser,
+                browser_version: r.browser_version,
+                form_factor: r.form_factor,
+            },
+            hints: DeviceHints {
+                memory: r.device_memory,
+                platform: r.platform,
+                mobile: r.mobile,
+            },
+            benches,
+        })
+    }
+
+    /// delete the submission identified by `proof`. Benches and tokens are removed by
+    /// `ON DELETE CASCADE`
+    pub async fn delete_submission(
+        proof: &SubmissionProof,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        let (user_id, proof_id) = proof.parse()?;
+
+        let res = sqlx::query!(
+            "DELETE FROM survey_responses
+            WHERE ID = (
+                SELECT resp_id
+                FROM survey_response_tokens
+                WHERE
+                    ID = $1
+                AND
+                    user_id = $2
+            )",
+            &proof_id,
+            &user_id,
+        )
+        .execute(&data.db)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            Err(ServiceError::SubmissionNotFound)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 #[actix_web_codegen_const_routes::get(path = "crate::V1_API_ROUTES.benches.register")]
@@ -157,39 +621,209 @@ async fn register(
     }
 }
 
+/// A single timed sample. Campaigns with more than one repetition receive several
+/// benches per difficulty.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bench {
     pub duration: f32,
     pub difficulty: i32,
 }
 
+/// A timed sample as submitted by the participant, along with the nonce that solves
+/// the campaign's PoW challenge at `difficulty`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmittedBench {
+    pub duration: f32,
+    pub difficulty: i32,
+    pub nonce: u64,
+}
+
+impl From<SubmittedBench> for Bench {
+    fn from(b: SubmittedBench) -> Self {
+        Self {
+            duration: b.duration,
+            difficulty: b.difficulty,
+        }
+    }
+}
+
+/// All samples collected for a difficulty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchSummary {
+    pub difficulty: i32,
+    pub samples: Vec<f32>,
+    pub min: f32,
+    pub median: f32,
+}
+
+impl BenchSummary {
+    /// group `benches` by difficulty, in ascending order of difficulty
+    pub fn summarize(benches: &[Bench]) -> Vec<BenchSummary> {
+        let mut difficulties: Vec<i32> = benches.iter().map(|b| b.difficulty).collect();
+        difficulties.sort_unstable();
+        difficulties.dedup();
+
+        difficulties
+            .drain(0..)
+            .map(|difficulty| {
+                let samples: Vec<f32> = benches
+                    .iter()
+                    .filter(|b| b.difficulty == difficulty)
+                    .map(|b| b.duration)
+                    .collect();
+                let mut sorted = samples.clone();
+                sorted.sort_by(|a, b| a.total_cmp(b));
+                let mid = sorted.len() / 2;
+                let median = if sorted.len() % 2 == 0 {
+                    (sorted[mid - 1] + sorted[mid]) / 2.0
+                } else {
+                    sorted[mid]
+                };
+                BenchSummary {
+                    difficulty,
+                    min: sorted[0],
+                    median,
+                    samples,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Hashrate of a device, estimated from the least-squares line of duration against
+/// difficulty. Solving a PoW of difficulty factor `d` takes `d` hashes on average, so
+/// the slope is the time per hash and the intercept absorbs fixed overheads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashrateFit {
+    /// hashes per second
+    pub hashrate: f64,
+    /// coefficient of determination of the fit, between 0 and 1
+    pub r2: f64,
+}
+
+impl HashrateFit {
+    /// `None` unless `benches` span at least two difficulties and duration grows
+    /// with difficulty
+    pub fn fit(benches: &[Bench]) -> Option<HashrateFit> {
+        let n = benches.len() as f64;
+        let mean_x = benches.iter().map(|b| b.difficulty as f64).sum::<f64>() / n;
+        let mean_y = benches.iter().map(|b| b.duration as f64).sum::<f64>() / n;
+
+        let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+        for b in benches.iter() {
+            let dx = b.difficulty as f64 - mean_x;
+            let dy = b.duration as f64 - mean_y;
+            sxx += dx * dx;
+            sxy += dx * dy;
+            syy += dy * dy;
+        }
+
+        // durations are in milliseconds
+        let slope = sxy / sxx;
+        if !(slope > 0.0 && slope.is_finite()) {
+            return None;
+        }
+        Some(HashrateFit {
+            hashrate: 1000.0 / slope,
+            r2: sxy * sxy / (sxx * syy),
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Submission {
     pub device_user_provided: String,
     pub device_software_recognised: String,
     pub threads: i32,
-    pub benches: Vec<Bench>,
+    pub benches: Vec<SubmittedBench>,
     pub submission_type: SubmissionType,
+    /// client-generated key; a replayed submission with the same key returns the
+    /// original [SubmissionProof] instead of creating a new response
+    pub idempotency_key: Option<String>,
+    #[serde(default)]
+    pub hints: DeviceHints,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum SubmissionType {
-    Wasm,
-    Js,
+impl Submission {
+    /// check submission against the configuration of the campaign that it is
+    /// submitted to
+    pub fn validate(&self, config: &BenchConfig) -> ServiceResult<()> {
+        if self.device_user_provided.chars().count() > MAX_DEVICE_INFO_LEN
+            || self.device_software_recognised.chars().count() > MAX_DEVICE_INFO_LEN
+        {
+            return Err(ServiceError::DeviceInfoTooLong);
+        }
+
+        self.hints.validate()?;
+
+        if self.threads < 1 || self.threads > MAX_THREADS {
+            return Err(ServiceError::InvalidThreadCount);
+        }
+
+        if let Some(key) = &self.idempotency_key {
+            let len = key.chars().count();
+            if len == 0 || len > MAX_IDEMPOTENCY_KEY_LEN {
+                return Err(ServiceError::InvalidIdempotencyKey);
+            }
+        }
+
+        if self.benches.is_empty() {
+            return Err(ServiceError::NoBenches);
+        }
+
+        for bench in self.benches.iter() {
+            if !config.difficulties.contains(&bench.difficulty) {
+                return Err(ServiceError::DifficultyNotInCampaign);
+            }
+            let samples = self
+                .benches
+                .iter()
+                .filter(|b| b.difficulty == bench.difficulty)
+                .count();
+            if samples > config.repetitions as usize {
+                return Err(ServiceError::TooManySamples);
+            }
+            if !bench.duration.is_finite() || bench.duration < 0.0 {
+                return Err(ServiceError::InvalidDuration);
+            }
+            if !pow::verify(&config.salt, &config.phrase, bench.nonce, bench.difficulty)
+            {
+                return Err(ServiceError::InvalidProof);
+            }
+        }
+        Ok(())
+    }
 }
 
-impl ToString for SubmissionType {
-    fn to_string(&self) -> String {
-        let s = serde_json::to_string(&self).unwrap();
-        (&s[1..(s.len() - 1)]).to_string()
+/// Name of a benchmark type registered in `survey_bench_type`, see
+/// [crate::api::v1::admin::bench_types]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SubmissionType(String);
+
+impl SubmissionType {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// mCaptcha's WASM PoW library
+    pub fn wasm() -> Self {
+        Self::new("wasm")
+    }
+
+    /// mCaptcha's JavaScript polyfill, used when WASM isn't available
+    pub fn js() -> Self {
+        Self::new("js")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
     }
 }
 
-impl FromStr for SubmissionType {
-    type Err = serde_json::Error;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        serde_json::from_str(&format!("\"{}\"", s))
+impl ToString for SubmissionType {
+    fn to_string(&self) -> String {
+        self.0.clone()
     }
 }
 
@@ -199,6 +833,31 @@ pub struct SubmissionProof {
     pub proof: String,
 }
 
+impl SubmissionProof {
+    /// returns survey user ID and submission token ID
+    fn parse(&self) -> ServiceResult<(Uuid, Uuid)> {
+        let user_id = Uuid::parse_str(&self.token)
+            .map_err(|_| ServiceError::SubmissionNotFound)?;
+        let proof_id = Uuid::parse_str(&self.proof)
+            .map_err(|_| ServiceError::SubmissionNotFound)?;
+        Ok((user_id, proof_id))
+    }
+}
+
+/// Everything that is stored about a submission, as seen by the participant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmissionReceipt {
+    pub campaign_id: String,
+    pub device_user_provided: String,
+    pub device_software_recognised: String,
+    pub threads: Option<usize>,
+    pub submitted_at: i64,
+    pub submission_type: SubmissionType,
+    pub user_agent: UserAgent,
+    pub hints: DeviceHints,
+    pub benches: Vec<Bench>,
+}
+
 fn is_session_authenticated(r: &HttpRequest, pl: &mut Payload) -> bool {
     use actix_web::FromRequest;
     matches!(
@@ -235,84 +894,35 @@ async fn submit(
 
     let user_id = Uuid::from_str(&username).unwrap();
     let payload = payload.into_inner();
-    let now = OffsetDateTime::now_utc();
 
-    struct ID {
-        id: i32,
-    }
-    let resp_id = sqlx::query_as!(
-        ID,
-        "INSERT INTO survey_responses (
-                    user_id,
-                    campaign_id,
-                    device_user_provided,
-                    device_software_recognised,
-                    threads,
-                    submitted_at,
-                    submission_bench_type_id
-                ) VALUES (
-                    $1, $2, $3, $4, $5, $6,
-                    (SELECT ID FROM survey_bench_type WHERE name = $7)
-                )
-        RETURNING ID;",
-        &user_id,
-        &campaign_id,
-        &payload.device_user_provided,
-        &payload.device_software_recognised,
-        &payload.threads,
-        &now,
-        &payload.submission_type.to_string(),
-    )
-    .fetch_one(&data.db)
-    .await?;
-
-    let mut futs = Vec::with_capacity(payload.benches.len());
-    for bench in payload.benches.iter() {
-        let fut = sqlx::query!(
-            "INSERT INTO survey_benches 
-                (resp_id, difficulty, duration) 
-            VALUES ($1, $2, $3);",
-            &resp_id.id,
-            &bench.difficulty,
-            bench.duration
-        )
-        .execute(&data.db); //.await?;
-        futs.push(fut);
+    if let Some(key) = &payload.idempotency_key {
+        if let Some(submission_id) =
+            runners::get_idempotent_submission(&user_id, &campaign_id, key, &data)
+                .await?
+        {
+            let resp = SubmissionProof {
+                token: username,
+                proof: submission_id.to_string(),
+            };
+            return Ok(HttpResponse::Ok().json(resp));
+        }
     }
 
-    let mut submitions_id;
-    try_join_all(futs).await?;
-
-    loop {
-        submitions_id = get_uuid();
-
-        let res = sqlx::query!(
-            "INSERT INTO survey_response_tokens 
-            (resp_id, user_id, id)
-            VALUES ($1, $2, $3);",
-            &resp_id.id,
-            &user_id,
-            &submitions_id
-        )
-        .execute(&data.db)
-        .await;
-
-        if res.is_ok() {
-            break;
-        } else if let Err(sqlx::Error::Database(err)) = res {
-            if err.code() == Some(Cow::from("23505"))
-                && err.message().contains("survey_response_tokens_id_key")
-            {
-                continue;
-            } else {
-                return Err(sqlx::Error::Database(err).into());
-            }
-        }
+    let config = runners::get_bench_config(&campaign_id, &data)
+        .await?
+        .for_participant(&user_id);
+    payload.validate(&config)?;
+    if !bench_types::runners::is_active(&payload.submission_type, &data).await? {
+        return Err(ServiceError::UnknownBenchType);
     }
 
+    let submission_id =
+        runners::submit_runner(&user_id, &campaign_id, &payload, config.version, &data)
+            .await?;
+
     let resp = SubmissionProof {
         token: username,
-        proof: submitions_id.to_string(),
+        proof: submission_id.to_string(),
     };
 
     Ok(HttpResponse::Ok().json(resp))
@@ -321,33 +931,608 @@ async fn submit(
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BenchConfig {
     pub difficulties: Vec<i32>,
+    /// number of samples to collect for each difficulty
+    pub repetitions: i32,
+    /// salt of the campaign's PoW challenge
+    pub salt: String,
+    /// phrase of the campaign's PoW challenge
+    pub phrase: String,
+    /// version of the campaign configuration that `difficulties` belong to
+    pub version: i32,
+}
+
+impl BenchConfig {
+    /// configuration with the PoW challenge that participant `user_id` solves
+    pub fn for_participant(mut self, user_id: &Uuid) -> Self {
+        self.phrase = pow::participant_phrase(&self.phrase, user_id);
+        self
+    }
 }
 
 #[actix_web_codegen_const_routes::get(
     path = "crate::V1_API_ROUTES.benches.fetch",
     wrap = "get_check_login()"
 )]
-async fn fetch(data: AppData, path: web::Path<String>) -> ServiceResult<impl Responder> {
+async fn fetch(
+    data: AppData,
+    session: Session,
+    path: web::Path<String>,
+) -> ServiceResult<impl Responder> {
     let path = path.into_inner();
     let campaign_id = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    let username = session.get::<String>(SURVEY_USER_ID).unwrap().unwrap();
+    let user_id = Uuid::from_str(&username).unwrap();
 
-    let config = sqlx::query_as!(
-        BenchConfig,
-        "SELECT difficulties FROM survey_campaigns WHERE id = $1;",
-        &campaign_id,
-    )
-    .fetch_one(&data.db)
-    .await?;
+    check_accepting_responses(&campaign_id, Some(&user_id), &data).await?;
+    let config = runners::get_bench_config(&campaign_id, &data)
+        .await?
+        .for_participant(&user_id);
     Ok(HttpResponse::Ok().json(config))
 }
 
+/// Public information about a campaign, shown to participants before they start
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignInfo {
+    pub name: String,
+    /// Markdown
+    pub description: Option<String>,
+    /// Markdown
+    pub instructions: Option<String>,
+    pub status: CampaignStatus,
+    pub difficulties: Vec<i32>,
+}
+
+#[actix_web_codegen_const_routes::get(path = "crate::V1_API_ROUTES.benches.info")]
+async fn info(data: AppData, path: web::Path<String>) -> ServiceResult<impl Responder> {
+    let path = path.into_inner();
+    let campaign_id = Uuid::parse_str(&path).map_err(|_| ServiceError::NotAnId)?;
+    let info = runners::get_campaign_info(&campaign_id, &data).await?;
+    Ok(HttpResponse::Ok().json(info))
+}
+
+#[actix_web_codegen_const_routes::post(path = "crate::V1_API_ROUTES.benches.receipt")]
+async fn receipt(
+    data: AppData,
+    payload: web::Json<SubmissionProof>,
+) -> ServiceResult<impl Responder> {
+    let receipt = runners::get_receipt(&payload, &data).await?;
+    Ok(HttpResponse::Ok().json(receipt))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "crate::V1_API_ROUTES.benches.delete_submission"
+)]
+async fn delete_submission(
+    data: AppData,
+    payload: web::Json<SubmissionProof>,
+) -> ServiceResult<impl Responder> {
+    runners::delete_submission(&payload, &data).await?;
+    Ok(HttpResponse::Ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn survey_response_type_no_panic_test() {
-        assert_eq!(SubmissionType::Wasm.to_string(), "wasm".to_string());
-        assert_eq!(SubmissionType::Js.to_string(), "js".to_string());
+        assert_eq!(SubmissionType::wasm().to_string(), "wasm".to_string());
+        assert_eq!(SubmissionType::js().to_string(), "js".to_string());
+    }
+
+    #[test]
+    fn bench_summary_works() {
+        let bench = |difficulty, duration| Bench {
+            difficulty,
+            duration,
+        };
+        let benches = [
+            bench(2, 4.0),
+            bench(1, 3.0),
+            bench(2, 1.0),
+            bench(1, 1.0),
+            bench(1, 2.0),
+            bench(2, 2.0),
+            bench(2, 9.0),
+        ];
+
+        let summary = BenchSummary::summarize(&benches);
+        assert_eq!(
+            summary,
+            vec![
+                BenchSummary {
+                    difficulty: 1,
+                    samples: vec![3.0, 1.0, 2.0],
+                    min: 1.0,
+                    median: 2.0,
+                },
+                BenchSummary {
+                    difficulty: 2,
+                    samples: vec![4.0, 1.0, 2.0, 9.0],
+                    min: 1.0,
+                    median: 3.0,
+                },
+            ]
+        );
+        assert!(BenchSummary::summarize(&[]).is_empty());
+    }
+
+    #[test]
+    fn hashrate_fit_works() {
+        let bench = |difficulty, duration| Bench {
+            difficulty,
+            duration,
+        };
+
+        // 2ms overhead and half a millisecond per hash
+        let benches = [bench(10, 7.0), bench(20, 12.0), bench(40, 22.0)];
+        let fit = HashrateFit::fit(&benches).unwrap();
+        assert!((fit.hashrate - 2000.0).abs() < 1e-6);
+        assert!((fit.r2 - 1.0).abs() < 1e-9);
+
+        let noisy = [bench(10, 8.0), bench(20, 11.0), bench(40, 23.0)];
+        let fit = HashrateFit::fit(&noisy).unwrap();
+        assert!(fit.r2 < 1.0 && fit.r2 > 0.9);
+
+        // a single difficulty has no slope, and durations must grow with difficulty
+        assert!(HashrateFit::fit(&[bench(10, 7.0), bench(10, 9.0)]).is_none());
+        assert!(HashrateFit::fit(&[bench(10, 9.0), bench(20, 7.0)]).is_none());
+        assert!(HashrateFit::fit(&[]).is_none());
+    }
+
+    #[test]
+    fn submission_validation_works() {
+        let config = BenchConfig {
+            difficulties: vec![1, 2, 3],
+            repetitions: 2,
+            salt: "submissionvalidationsalt".into(),
+            phrase: "submissionvalidationphrase".into(),
+            version: 1,
+        };
+        let bench = |difficulty, duration| SubmittedBench {
+            difficulty,
+            duration,
+            nonce: pow::solve(&config.salt, &config.phrase, difficulty),
+        };
+
+        let valid = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "Foobar.v2".into(),
+            threads: 4,
+            benches: vec![bench(1, 1.0), bench(3, 3.0)],
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        assert!(valid.validate(&config).is_ok());
+
+        let mut s = valid.clone();
+        s.benches.clear();
+        assert_eq!(s.validate(&config), Err(ServiceError::NoBenches));
+
+        let mut s = valid.clone();
+        s.benches[1].difficulty = 4;
+        assert_eq!(
+            s.validate(&config),
+            Err(ServiceError::DifficultyNotInCampaign)
+        );
+
+        let mut s = valid.clone();
+        s.benches[1].difficulty = 1;
+        assert!(s.validate(&config).is_ok());
+        s.benches.push(bench(1, 1.5));
+        assert_eq!(s.validate(&config), Err(ServiceError::TooManySamples));
+
+        let mut s = valid.clone();
+        s.benches[1].nonce = (0..)
+            .find(|n| !pow::verify(&config.salt, &config.phrase, *n, 3))
+            .unwrap();
+        assert_eq!(s.validate(&config), Err(ServiceError::InvalidProof));
+
+        for duration in [-1.0, f32::NAN, f32::INFINITY] {
+            let mut s = valid.clone();
+            s.benches[0].duration = duration;
+            assert_eq!(s.validate(&config), Err(ServiceError::InvalidDuration));
+        }
+
+        for threads in [0, -1, MAX_THREADS + 1] {
+            let mut s = valid.clone();
+            s.threads = threads;
+            assert_eq!(s.validate(&config), Err(ServiceError::InvalidThreadCount));
+        }
+
+        let mut s = valid.clone();
+        s.device_user_provided = "a".repeat(MAX_DEVICE_INFO_LEN + 1);
+        assert_eq!(s.validate(&config), Err(ServiceError::DeviceInfoTooLong));
+
+        let mut s = valid.clone();
+        s.device_software_recognised = "a".repeat(MAX_DEVICE_INFO_LEN + 1);
+        assert_eq!(s.validate(&config), Err(ServiceError::DeviceInfoTooLong));
+
+        let mut s = valid.clone();
+        s.idempotency_key = Some("a".repeat(MAX_IDEMPOTENCY_KEY_LEN));
+        assert!(s.validate(&config).is_ok());
+        s.idempotency_key = Some("a".repeat(MAX_IDEMPOTENCY_KEY_LEN + 1));
+        assert_eq!(
+            s.validate(&config),
+            Err(ServiceError::InvalidIdempotencyKey)
+        );
+
+        let mut s = valid;
+        s.idempotency_key = Some("".into());
+        assert_eq!(
+            s.validate(&config),
+            Err(ServiceError::InvalidIdempotencyKey)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn submission_receipt_works() {
+        use actix_web::http::StatusCode;
+        use actix_web::test;
+
+        use crate::tests::*;
+        use crate::*;
+
+        const NAME: &str = "receiptuser";
+        const EMAIL: &str = "receiptuser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "receiptuser.v2".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints {
+                memory: Some(4.0),
+                platform: Some("Linux".into()),
+                mobile: Some(false),
+            },
+        };
+        let proof =
+            submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&proof, V1_API_ROUTES.benches.receipt).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let receipt: SubmissionReceipt = test::read_body_json(resp).await;
+        assert_eq!(receipt.campaign_id, campaign.campaign_id);
+        assert_eq!(
+            receipt.device_user_provided,
+            submit_payload.device_user_provided
+        );
+        assert_eq!(
+            receipt.device_software_recognised,
+            submit_payload.device_software_recognised
+        );
+        assert_eq!(receipt.threads, Some(submit_payload.threads as usize));
+        assert_eq!(receipt.submission_type, SubmissionType::wasm());
+        assert_eq!(receipt.user_agent, UserAgent::default());
+        assert_eq!(receipt.hints, submit_payload.hints);
+        assert_eq!(receipt.benches, *BENCHES);
+
+        let mut forged = proof.clone();
+        forged.token = crate::api::v1::get_uuid().to_string();
+        let resp = test::call_service(
+            &app,
+            post_request!(&forged, V1_API_ROUTES.benches.delete_submission).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&proof, V1_API_ROUTES.benches.delete_submission).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        for route in [
+            V1_API_ROUTES.benches.receipt,
+            V1_API_ROUTES.benches.delete_submission,
+        ] {
+            let resp =
+                test::call_service(&app, post_request!(&proof, route).to_request())
+                    .await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn submission_limits_work() {
+        use actix_web::http::StatusCode;
+        use actix_web::test;
+        use actix_web::ResponseError;
+
+        use crate::api::v1::admin::campaigns::{CampaignLifecycle, CampaignState};
+        use crate::tests::*;
+        use crate::*;
+
+        const NAME: &str = "submissionlimituser";
+        const EMAIL: &str = "submissionlimituser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let mut submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "submissionlimituser.v1".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: Some("retry-me".into()),
+            hints: DeviceHints::default(),
+        };
+
+        // fabricated proofs are rejected and don't count towards the limit
+        let config =
+            get_campaign_config(&campaign, data.clone(), survey_cookie.clone()).await;
+        let mut forged = submit_payload.clone();
+        let last = forged.benches.last_mut().unwrap();
+        let difficulty = last.difficulty;
+        last.nonce = (0..)
+            .find(|n| !pow::verify(&config.salt, &config.phrase, *n, difficulty))
+            .unwrap();
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &forged,
+                &V1_API_ROUTES.benches.submit_route(&campaign.campaign_id)
+            )
+            .cookie(survey_cookie.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::InvalidProof.status_code());
+        let proof = submit_bench(
+            &submit_payload,
+            &campaign,
+            survey_cookie.clone(),
+            data.clone(),
+        )
+        .await;
+        let replayed = submit_bench(
+            &submit_payload,
+            &campaign,
+            survey_cookie.clone(),
+            data.clone(),
+        )
+        .await;
+        assert_eq!(proof.proof, replayed.proof);
+
+        let lifecycle = CampaignLifecycle {
+            state: CampaignState::Open,
+            starts_at: None,
+            ends_at: None,
+            max_responses: None,
+            max_responses_per_participant: Some(1),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &lifecycle,
+                &V1_API_ROUTES
+                    .admin
+                    .campaign
+                    .get_lifecycle_route(&campaign.campaign_id)
+            )
+            .cookie(cookies.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // replays are still answered once the limit is reached
+        let replayed = submit_bench(
+            &submit_payload,
+            &campaign,
+            survey_cookie.clone(),
+            data.clone(),
+        )
+        .await;
+        assert_eq!(proof.proof, replayed.proof);
+
+        let route = V1_API_ROUTES.benches.submit_route(&campaign.campaign_id);
+        submit_payload.idempotency_key = None;
+        let resp = test::call_service(
+            &app,
+            post_request!(&submit_payload, &route)
+                .cookie(survey_cookie.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(
+            resp.status(),
+            ServiceError::SubmissionLimitReached.status_code()
+        );
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES.benches.fetch_routes(&campaign.campaign_id),
+            survey_cookie.clone()
+        );
+        assert_eq!(
+            resp.status(),
+            ServiceError::SubmissionLimitReached.status_code()
+        );
+
+        // limits are enforced when the response is written, not only by the handler
+        let campaign_id = Uuid::parse_str(&campaign.campaign_id).unwrap();
+        let user_id = Uuid::parse_str(&proof.token).unwrap();
+        let app_data = AppData::new(data.clone());
+        assert_eq!(
+            runners::submit_runner(
+                &user_id,
+                &campaign_id,
+                &submit_payload,
+                config.version,
+                &app_data
+            )
+            .await,
+            Err(ServiceError::SubmissionLimitReached)
+        );
+        submit_payload.idempotency_key = Some("retry-me".into());
+        let submission_id = runners::submit_runner(
+            &user_id,
+            &campaign_id,
+            &submit_payload,
+            config.version,
+            &app_data,
+        )
+        .await
+        .unwrap();
+        assert_eq!(submission_id.to_string(), proof.proof);
+    }
+
+    #[actix_rt::test]
+    async fn campaign_info_works() {
+        use actix_web::http::StatusCode;
+        use actix_web::test;
+        use actix_web::ResponseError;
+
+        use crate::api::v1::admin::campaigns::{
+            AddCapmaign, AddCapmaignResp, MAX_DESCRIPTION_LEN,
+        };
+        use crate::tests::*;
+        use crate::*;
+
+        const NAME: &str = "campaigninfouser";
+        const EMAIL: &str = "campaigninfouser@testadminuser.com";
+        const PASSWORD: &str = "longpassword2";
+        const DESCRIPTION: &str = "Measures PoW performance on phones";
+        const INSTRUCTIONS: &str = "Takes *about 5 minutes*";
+
+        {
+            let data = get_test_data().await;
+            delete_user(NAME, &data).await;
+        }
+
+        let (data, _creds, signin_resp) =
+            register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+        let app = get_app!(data).await;
+
+        let mut add = AddCapmaign {
+            name: NAME.into(),
+            difficulties: vec![1, 2, 3],
+            repetitions: 1,
+            description: Some("a".repeat(MAX_DESCRIPTION_LEN + 1)),
+            instructions: None,
+        };
+        bad_post_req_test(
+            NAME,
+            PASSWORD,
+            V1_API_ROUTES.admin.campaign.add,
+            &add,
+            ServiceError::CampaignDescriptionTooLong,
+        )
+        .await;
+
+        add.description = Some(DESCRIPTION.into());
+        add.instructions = Some(INSTRUCTIONS.into());
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, V1_API_ROUTES.admin.campaign.add)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let campaign: AddCapmaignResp = test::read_body_json(resp).await;
+
+        // available without a survey session
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES.benches.info_route(&campaign.campaign_id)
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let info: CampaignInfo = test::read_body_json(resp).await;
+        assert_eq!(
+            info,
+            CampaignInfo {
+                name: NAME.into(),
+                description: Some(DESCRIPTION.into()),
+                instructions: Some(INSTRUCTIONS.into()),
+                status: CampaignStatus::Open,
+                difficulties: vec![1, 2, 3],
+            }
+        );
+
+        let resp = get_request!(
+            &app,
+            &PAGES.panel.campaigns.get_about_route(&campaign.campaign_id)
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(DESCRIPTION));
+        assert!(body.contains("<em>about 5 minutes</em>"));
+
+        let resp = get_request!(
+            &app,
+            &PAGES.panel.campaigns.get_bench_route(&campaign.campaign_id),
+            survey_cookie.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("<em>about 5 minutes</em>"));
+
+        let unknown = crate::api::v1::get_uuid().to_string();
+        let resp = get_request!(&app, &V1_API_ROUTES.benches.info_route(&unknown));
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = get_request!(
+            &app,
+            &V1_API_ROUTES.benches.fetch_routes(&unknown),
+            survey_cookie.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = get_request!(&app, &PAGES.panel.campaigns.get_about_route(&unknown));
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = get_request!(&app, &V1_API_ROUTES.benches.info_route("foo"));
+        assert_eq!(resp.status(), ServiceError::NotAnId.status_code());
     }
 }
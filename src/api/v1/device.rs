@@ -0,0 +1,133 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Structured device metadata attached to benchmark submissions
+use serde::{Deserialize, Serialize};
+use woothee::parser::Parser;
+use woothee::woothee::VALUE_UNKNOWN;
+
+use crate::errors::*;
+
+/// length of `survey_responses.platform`
+pub const MAX_PLATFORM_LEN: usize = 100;
+
+/// Normalized fields parsed from the user agent at submit time. Fields that
+/// couldn't be recognised are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserAgent {
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    pub browser: Option<String>,
+    pub browser_version: Option<String>,
+    /// one of `pc`, `smartphone`, `mobilephone`, `appliance`, `crawler` or `misc`
+    pub form_factor: Option<String>,
+}
+
+impl UserAgent {
+    pub fn parse(user_agent: &str) -> Self {
+        let known = |v: &str| {
+            if v.is_empty() || v == VALUE_UNKNOWN {
+                None
+            } else {
+                Some(v.to_string())
+            }
+        };
+
+        match Parser::new().parse(user_agent) {
+            Some(r) => Self {
+                os: known(r.os),
+                os_version: known(&r.os_version),
+                browser: known(r.name),
+                browser_version: known(r.version),
+                form_factor: known(r.category),
+            },
+            None => Self::default(),
+        }
+    }
+}
+
+/// Optional hardware hints reported by the participant's browser
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceHints {
+    /// `navigator.deviceMemory`, in GiB
+    pub memory: Option<f32>,
+    /// `navigator.userAgentData.platform`, falling back to `navigator.platform`
+    pub platform: Option<String>,
+    /// `navigator.userAgentData.mobile`
+    pub mobile: Option<bool>,
+}
+
+impl DeviceHints {
+    pub fn validate(&self) -> ServiceResult<()> {
+        if let Some(memory) = self.memory {
+            if !memory.is_finite() || memory < 0.0 {
+                return Err(ServiceError::InvalidDeviceMemory);
+            }
+        }
+        if let Some(platform) = &self.platform {
+            if platform.chars().count() > MAX_PLATFORM_LEN {
+                return Err(ServiceError::DeviceInfoTooLong);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_agent_parsing_works() {
+        const FIREFOX_LINUX: &str =
+            "Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/111.0";
+        let ua = UserAgent::parse(FIREFOX_LINUX);
+        assert_eq!(ua.os.as_deref(), Some("Linux"));
+        assert_eq!(ua.browser.as_deref(), Some("Firefox"));
+        assert_eq!(ua.browser_version.as_deref(), Some("111.0"));
+        assert_eq!(ua.form_factor.as_deref(), Some("pc"));
+
+        const CHROME_ANDROID: &str = "Mozilla/5.0 (Linux; Android 13; Pixel 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/111.0.0.0 Mobile Safari/537.36";
+        let ua = UserAgent::parse(CHROME_ANDROID);
+        assert_eq!(ua.os.as_deref(), Some("Android"));
+        assert_eq!(ua.os_version.as_deref(), Some("13"));
+        assert_eq!(ua.browser.as_deref(), Some("Chrome"));
+        assert_eq!(ua.form_factor.as_deref(), Some("smartphone"));
+
+        assert_eq!(UserAgent::parse("foo"), UserAgent::default());
+        assert_eq!(UserAgent::parse(""), UserAgent::default());
+    }
+
+    #[test]
+    fn device_hints_validation_works() {
+        let mut hints = DeviceHints {
+            memory: Some(8.0),
+            platform: Some("Linux".into()),
+            mobile: Some(false),
+        };
+        assert!(hints.validate().is_ok());
+        assert!(DeviceHints::default().validate().is_ok());
+
+        hints.platform = Some("a".repeat(MAX_PLATFORM_LEN + 1));
+        assert_eq!(hints.validate(), Err(ServiceError::DeviceInfoTooLong));
+
+        hints.platform = None;
+        for memory in [-1.0, f32::NAN, f32::INFINITY] {
+            hints.memory = Some(memory);
+            assert_eq!(hints.validate(), Err(ServiceError::InvalidDeviceMemory));
+        }
+    }
+}
@@ -20,7 +20,9 @@ use sqlx::types::Uuid;
 
 pub mod admin;
 pub mod bench;
+pub mod device;
 mod meta;
+pub mod pow;
 pub mod routes;
 pub use routes::ROUTES;
 
@@ -0,0 +1,117 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Verification of mCaptcha's SHA-256 proof-of-work, as computed by
+//! `@mcaptcha/pow-wasm` and `@mcaptcha/pow_sha256-polyfill`
+use sha2::{Digest, Sha256};
+use sqlx::types::Uuid;
+
+use super::get_random;
+
+/// length of generated salts and phrases
+pub const CHALLENGE_LEN: usize = 32;
+
+/// generate a random salt or phrase for a campaign's PoW challenge
+pub fn get_challenge_string() -> String {
+    get_random(CHALLENGE_LEN)
+}
+
+/// phrase of the challenge that participant `user_id` solves, so that a solution
+/// can't be replayed by other participants
+pub fn participant_phrase(phrase: &str, user_id: &Uuid) -> String {
+    format!("{}{}", phrase, user_id)
+}
+
+/// smallest score that satisfies `difficulty_factor`
+pub fn get_difficulty(difficulty_factor: i32) -> u128 {
+    if difficulty_factor <= 1 {
+        0
+    } else {
+        u128::MAX - u128::MAX / difficulty_factor as u128
+    }
+}
+
+/// SHA-256 over the salt, the bincode-serialized phrase and the decimal nonce. The
+/// score is the first 16 bytes of the digest, read as a big-endian integer.
+pub fn score(salt: &str, phrase: &str, nonce: u64) -> u128 {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update((phrase.len() as u64).to_le_bytes());
+    hasher.update(phrase.as_bytes());
+    hasher.update(nonce.to_string().as_bytes());
+    let digest = hasher.finalize();
+
+    let mut first_bytes = [0; 16];
+    first_bytes.copy_from_slice(&digest[..16]);
+    u128::from_be_bytes(first_bytes)
+}
+
+/// checks if `nonce` solves the challenge at `difficulty_factor`
+pub fn verify(salt: &str, phrase: &str, nonce: u64, difficulty_factor: i32) -> bool {
+    score(salt, phrase, nonce) >= get_difficulty(difficulty_factor)
+}
+
+/// find the smallest nonce that solves the challenge at `difficulty_factor`
+#[cfg(test)]
+pub fn solve(salt: &str, phrase: &str, difficulty_factor: i32) -> u64 {
+    (0..)
+        .find(|nonce| verify(salt, phrase, *nonce, difficulty_factor))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALT: &str = "674243647f1c355da8607a8cdda05120d79ca5d1af8b3b49359d056a0a82";
+    const PHRASE: &str = "6e2a53dbc7d307970d7ba3c0000221722cb74f1c325137251ce8fa5c2240";
+
+    #[test]
+    fn pow_verification_works() {
+        // generated with mcaptcha-browser's gen_pow(SALT, PHRASE, 5000)
+        assert_eq!(
+            score(SALT, PHRASE, 5010),
+            340265996767074229071658204693669755833
+        );
+        assert!(verify(SALT, PHRASE, 5010, 5000));
+        assert_eq!(solve(SALT, PHRASE, 5000), 5010);
+        assert!(!verify(SALT, PHRASE, 5010, 5000 * 1000));
+
+        // generated with mcaptcha-browser's gen_pow("salt", "phrase", 100)
+        assert_eq!(
+            score("salt", "phrase", 124),
+            337755446616101326734092741027482807701
+        );
+        assert_eq!(solve("salt", "phrase", 100), 124);
+
+        // a solution only holds for the participant that it was issued to
+        let alice = Uuid::parse_str("fd3bd3a4-1bd1-4b38-8a2b-3f2dcbf8a3b7").unwrap();
+        let bob = Uuid::parse_str("0b3c0b1e-6b34-4f0f-9d1a-6e4c0c8f1f5a").unwrap();
+        let alice_phrase = participant_phrase(PHRASE, &alice);
+        let nonce = solve(SALT, &alice_phrase, 5000);
+        assert_eq!(nonce, 253);
+        assert!(!verify(
+            SALT,
+            &participant_phrase(PHRASE, &bob),
+            nonce,
+            5000
+        ));
+
+        assert_eq!(get_difficulty(1), 0);
+        assert_eq!(get_difficulty(0), 0);
+        assert!(verify(SALT, PHRASE, 0, 1));
+    }
+}
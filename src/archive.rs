@@ -24,12 +24,17 @@ use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::oneshot::{self, error::TryRecvError, Sender};
 
-use crate::api::v1::admin::campaigns::runners::get_results;
-use crate::api::v1::admin::campaigns::SurveyResponse;
+use crate::api::v1::admin::campaigns::runners::{get_config_versions, get_results};
+use crate::api::v1::admin::campaigns::{
+    CampaignConfigVersion, ResultsPage, SurveyResponse,
+};
+use crate::api::v1::admin::review::ReviewState;
+use crate::api::v1::admin::stats::{self, CampaignHashrate};
 use crate::{errors::ServiceResult, AppData, Settings};
 
 const CAMPAIGN_INFO_FILE: &str = "campaign.json";
 const BENCHMARK_FILE: &str = "benchmark.csv";
+const HASHRATE_FILE: &str = "hashrate.json";
 
 pub struct Archiver {
     base_path: String,
@@ -65,6 +70,10 @@ impl Archive {
     fn benchmark_file_path(&self) -> PathBuf {
         self.archive_path_now().join(BENCHMARK_FILE)
     }
+
+    fn hashrate_file_path(&self) -> PathBuf {
+        self.archive_path_now().join(HASHRATE_FILE)
+    }
 }
 
 impl Archiver {
@@ -99,6 +108,24 @@ impl Archiver {
         Ok(())
     }
 
+    async fn write_hashrate_file(
+        &self,
+        c: &Campaign,
+        a: &Archive,
+        data: &AppData,
+    ) -> ServiceResult<()> {
+        let archive_path = a.archive_path_now();
+        Self::create_dir_util(&archive_path).await?;
+        let campaign_id = Uuid::parse_str(&c.id.to_string()).unwrap();
+        let hashrate = stats::runners::hashrate(&campaign_id, data).await?;
+        let contents = serde_json::to_string(&hashrate).unwrap();
+        let mut file = fs::File::create(&a.hashrate_file_path()).await.unwrap();
+        file.write_all(contents.as_bytes()).await.unwrap();
+        file.flush().await.unwrap();
+
+        Ok(())
+    }
+
     fn get_headers(c: &Campaign) -> Vec<String> {
         let mut keys = vec![
             "ID".to_string(),
@@ -110,13 +137,34 @@ impl Archiver {
             "submission_type".to_string(),
         ];
 
-        let mut diff_order = Vec::with_capacity(c.difficulties.len());
+        let difficulties = c.all_difficulties();
 
-        for d in c.difficulties.iter() {
-            diff_order.push(d);
+        // median of the samples, which is the only sample of single-sample campaigns
+        for d in difficulties.iter() {
             keys.push(format!("Difficulty {}", d));
         }
 
+        // columns added after the first published format go last, so that the
+        // columns of earlier archives keep their positions
+        keys.push("os".to_string());
+        keys.push("os_version".to_string());
+        keys.push("browser".to_string());
+        keys.push("browser_version".to_string());
+        keys.push("form_factor".to_string());
+        keys.push("device_memory".to_string());
+        keys.push("platform".to_string());
+        keys.push("mobile".to_string());
+        for d in difficulties.iter() {
+            keys.push(format!("Difficulty {} min", d));
+        }
+        for d in difficulties.iter() {
+            keys.push(format!("Difficulty {} samples", d));
+        }
+        keys.push("config_version".to_string());
+        keys.push("hashrate".to_string());
+        keys.push("hashrate_r2".to_string());
+        keys.push("canonical_device".to_string());
+
         keys
     }
 
@@ -130,14 +178,42 @@ impl Archiver {
             r.submitted_at.to_string(),
             r.submission_type.to_string(),
         ];
-        for d in c.difficulties.iter() {
-            let bench = r
-                .benches
-                .iter()
-                .find(|b| b.difficulty == *d as i32)
-                .map_or_else(|| "-".into(), |v| v.duration.to_string());
-            rec.push(bench);
+        let difficulties = c.all_difficulties();
+        let summaries = r.summary;
+        let summary = |d: &u32| summaries.iter().find(|s| s.difficulty == *d as i32);
+        for d in difficulties.iter() {
+            rec.push(summary(d).map_or_else(|| "-".into(), |s| s.median.to_string()));
+        }
+
+        rec.push(r.user_agent.os.unwrap_or_else(|| "-".into()));
+        rec.push(r.user_agent.os_version.unwrap_or_else(|| "-".into()));
+        rec.push(r.user_agent.browser.unwrap_or_else(|| "-".into()));
+        rec.push(r.user_agent.browser_version.unwrap_or_else(|| "-".into()));
+        rec.push(r.user_agent.form_factor.unwrap_or_else(|| "-".into()));
+        rec.push(r.hints.memory.map_or_else(|| "-".into(), |v| v.to_string()));
+        rec.push(r.hints.platform.unwrap_or_else(|| "-".into()));
+        rec.push(r.hints.mobile.map_or_else(|| "-".into(), |v| v.to_string()));
+        for d in difficulties.iter() {
+            rec.push(summary(d).map_or_else(|| "-".into(), |s| s.min.to_string()));
         }
+        for d in difficulties.iter() {
+            let samples = summary(d).map_or_else(
+                || "-".into(),
+                |s| {
+                    s.samples
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<String>>()
+                        .join(";")
+                },
+            );
+            rec.push(samples);
+        }
+        rec.push(r.config_version.to_string());
+        let fit = r.hashrate.as_ref();
+        rec.push(fit.map_or_else(|| "-".into(), |f| f.hashrate.to_string()));
+        rec.push(fit.map_or_else(|| "-".into(), |f| f.r2.to_string()));
+        rec.push(r.canonical_device.unwrap_or_else(|| "-".into()));
         rec
     }
 
@@ -170,7 +246,7 @@ impl Archiver {
         .fetch_one(&data.db)
         .await?;
 
-        let mut page = 0;
+        let mut page = ResultsPage::default();
         let limit = 50;
         let file = fs::OpenOptions::new()
             .read(true)
@@ -185,26 +261,29 @@ impl Archiver {
         wri.write_record(&keys).await.unwrap();
 
         loop {
-            let mut resp = get_results(
+            let resp = get_results(
                 &owner.name,
                 &Uuid::parse_str(&c.id.to_string()).unwrap(),
                 data,
-                page,
+                &page,
                 limit,
-                None,
             )
             .await?;
 
-            for r in resp.drain(0..) {
+            // excluded responses are kept in the database, but not published
+            for r in resp
+                .responses
+                .into_iter()
+                .filter(|r| r.review.state != ReviewState::Excluded)
+            {
                 let rec = Self::extract_record(c, r);
                 wri.write_record(&rec).await.unwrap();
                 wri.flush().await.unwrap();
             }
 
-            if resp.len() < limit {
-                break;
-            } else {
-                page += 1
+            match resp.next {
+                Some(next) => page = page.with_after(Some(next)),
+                None => break,
             }
         }
         Ok(())
@@ -247,15 +326,20 @@ impl Archiver {
     pub async fn archive(&self, data: &AppData) -> ServiceResult<()> {
         let mut db_campaigns = sqlx::query_as!(
             InnerCampaign,
-            "SELECT ID, name, difficulties, created_at FROM survey_campaigns"
+            "SELECT ID, name, difficulties, created_at
+            FROM survey_campaigns
+            WHERE deleted_at IS NULL"
         )
         .fetch_all(&data.db)
         .await?;
         for c in db_campaigns.drain(0..) {
             let archive = Archive::new(c.id.clone(), self.base_path.clone());
-            let campaign: Campaign = c.into();
+            let configs = get_config_versions(&c.id, data).await?;
+            let mut campaign: Campaign = c.into();
+            campaign.configs = configs;
             self.write_campaign_file(&campaign, &archive).await?;
             self.write_benchmark_file(&campaign, &archive, data).await?;
+            self.write_hashrate_file(&campaign, &archive, data).await?;
         }
         Ok(())
     }
@@ -275,6 +359,21 @@ pub struct Campaign {
     pub name: String,
     pub difficulties: Vec<u32>,
     pub created_at: i64,
+    /// every configuration version of the campaign, oldest first
+    pub configs: Vec<CampaignConfigVersion>,
+}
+
+impl Campaign {
+    /// difficulties used by any configuration version, in ascending order
+    fn all_difficulties(&self) -> Vec<u32> {
+        let mut difficulties = self.difficulties.clone();
+        for config in self.configs.iter() {
+            difficulties.extend(config.difficulties.iter().map(|d| *d as u32));
+        }
+        difficulties.sort_unstable();
+        difficulties.dedup();
+        difficulties
+    }
 }
 
 impl From<InnerCampaign> for Campaign {
@@ -284,6 +383,7 @@ impl From<InnerCampaign> for Campaign {
             name: i.name,
             difficulties: i.difficulties.iter().map(|d| *d as u32).collect(),
             created_at: i.created_at.unix_timestamp(),
+            configs: Vec::new(),
         }
     }
 }
@@ -297,6 +397,7 @@ mod tests {
 
     use crate::api::v1::bench::Submission;
     use crate::api::v1::bench::SubmissionType;
+    use crate::api::v1::device::DeviceHints;
     use crate::*;
 
     use super::*;
@@ -335,6 +436,71 @@ mod tests {
                 .join(&archive.now.to_string())
                 .join(BENCHMARK_FILE)
         );
+
+        let hashrate_file_path = archive.hashrate_file_path();
+        assert_eq!(
+            hashrate_file_path,
+            Path::new(&settings.publish.dir)
+                .join(&uuid.to_string())
+                .join(&archive.now.to_string())
+                .join(HASHRATE_FILE)
+        );
+    }
+
+    #[test]
+    fn archive_headers_cover_all_config_versions() {
+        let config = |version, difficulties: &[i32]| CampaignConfigVersion {
+            version,
+            difficulties: difficulties.to_vec(),
+            created_at: 0,
+        };
+        let campaign = Campaign {
+            id: uuid::Uuid::new_v4(),
+            name: "foo".into(),
+            difficulties: vec![2, 5],
+            created_at: 0,
+            configs: vec![config(1, &[1, 2, 3]), config(2, &[2, 5])],
+        };
+        assert_eq!(campaign.all_difficulties(), vec![1, 2, 3, 5]);
+
+        // the first published format is a prefix of the current one
+        let headers = Archiver::get_headers(&campaign);
+        assert_eq!(
+            headers,
+            vec![
+                "ID",
+                "user",
+                "device_user_provided",
+                "device_software_recognised",
+                "threads",
+                "submitted_at",
+                "submission_type",
+                "Difficulty 1",
+                "Difficulty 2",
+                "Difficulty 3",
+                "Difficulty 5",
+                "os",
+                "os_version",
+                "browser",
+                "browser_version",
+                "form_factor",
+                "device_memory",
+                "platform",
+                "mobile",
+                "Difficulty 1 min",
+                "Difficulty 2 min",
+                "Difficulty 3 min",
+                "Difficulty 5 min",
+                "Difficulty 1 samples",
+                "Difficulty 2 samples",
+                "Difficulty 3 samples",
+                "Difficulty 5 samples",
+                "config_version",
+                "hashrate",
+                "hashrate_r2",
+                "canonical_device",
+            ]
+        );
     }
 
     #[actix_rt::test]
@@ -374,8 +540,16 @@ mod tests {
             device_user_provided: DEVICE_USER_PROVIDED.into(),
             device_software_recognised: DEVICE_SOFTWARE_RECOGNISED.into(),
             threads: THREADS,
-            benches: BENCHES.clone(),
-            submission_type: SubmissionType::Wasm,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
         };
 
         let _proof =
@@ -389,7 +563,11 @@ mod tests {
         )
         .fetch_one(&data.db)
         .await.unwrap();
-        let campaign: Campaign = db_campaign.into();
+        let mut campaign: Campaign = db_campaign.into();
+        campaign.configs =
+            get_config_versions(&campaign_id, &AppData::new(data.clone()))
+                .await
+                .unwrap();
 
         let archive = Archive::new(
             Uuid::parse_str(&campaign.id.to_string()).unwrap(),
@@ -405,18 +583,17 @@ mod tests {
         .unwrap();
         assert_eq!(contents, campaign);
 
-        let page = 0;
         let limit = 10;
         let mut responses = get_results(
             NAME,
             &campaign_id,
             &AppData::new(data.clone()),
-            page,
+            &ResultsPage::default(),
             limit,
-            None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .responses;
         assert_eq!(responses.len(), 1);
         let r = responses.pop().unwrap();
         let rec = Archiver::extract_record(&campaign, r);
@@ -430,5 +607,19 @@ mod tests {
             records.next().await.unwrap().unwrap(),
             StringRecord::from(rec)
         );
+
+        let hashrate: CampaignHashrate = serde_json::from_str(
+            &fs::read_to_string(&archive.hashrate_file_path())
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            hashrate,
+            stats::runners::hashrate(&campaign_id, &AppData::new(data.clone()))
+                .await
+                .unwrap()
+        );
+        assert_eq!(hashrate.submission_types[0].hashrate.responses, 1);
     }
 }
@@ -82,6 +82,106 @@ pub enum ServiceError {
 
     #[display(fmt = "Not a number: only numeral data is accepted")]
     NotANumber,
+
+    #[display(fmt = "Submission must contain at least one benchmark")]
+    NoBenches,
+    /// when a bench is submitted for a difficulty that the campaign doesn't have
+    #[display(fmt = "Benchmark difficulty is not part of this campaign")]
+    DifficultyNotInCampaign,
+    /// when a difficulty is benchmarked more times than the campaign's repetition count
+    #[display(
+        fmt = "Benchmark difficulty was submitted more times than the campaign asks for"
+    )]
+    TooManySamples,
+    #[display(fmt = "Benchmark duration must be a non-negative number")]
+    InvalidDuration,
+    /// when a benchmark's nonce doesn't solve the campaign's PoW challenge
+    #[display(fmt = "Proof of work is invalid")]
+    InvalidProof,
+    #[display(fmt = "Thread count is out of range")]
+    InvalidThreadCount,
+    #[display(fmt = "Device information is too long")]
+    DeviceInfoTooLong,
+    #[display(fmt = "Device memory must be a non-negative number")]
+    InvalidDeviceMemory,
+
+    #[display(fmt = "Submission not found")]
+    SubmissionNotFound,
+
+    /// campaign is in draft or its start time is in the future
+    #[display(fmt = "Campaign isn't accepting responses yet")]
+    CampaignNotStarted,
+    /// campaign was closed, has ended or has reached its response limit
+    #[display(fmt = "Campaign is closed and no longer accepts responses")]
+    CampaignClosed,
+    #[display(fmt = "Campaign end time must be after its start time")]
+    InvalidCampaignSchedule,
+    #[display(fmt = "Maximum response count must be a positive number")]
+    InvalidResponseLimit,
+    #[display(fmt = "Repetition count is out of range")]
+    InvalidRepetitions,
+    #[display(fmt = "Difficulties must be distinct positive numbers")]
+    InvalidDifficulties,
+    #[display(fmt = "Campaign description is too long")]
+    CampaignDescriptionTooLong,
+    #[display(fmt = "Campaign instructions are too long")]
+    CampaignInstructionsTooLong,
+    /// participant has already submitted as many responses as the campaign allows
+    #[display(fmt = "You have already submitted the maximum number of responses")]
+    SubmissionLimitReached,
+    #[display(fmt = "Idempotency key must be between 1 and 100 characters long")]
+    InvalidIdempotencyKey,
+
+    /// submission type isn't registered or has been retired
+    #[display(fmt = "Unknown benchmark type")]
+    UnknownBenchType,
+    #[display(
+        fmt = "Benchmark type names must be 1 to 30 lowercase letters, numbers, '-' or '_'"
+    )]
+    InvalidBenchTypeName,
+    #[display(fmt = "Benchmark type already exists")]
+    BenchTypeExists,
+
+    #[display(fmt = "Template names must be between 1 and 100 characters long")]
+    InvalidTemplateName,
+    #[display(fmt = "Template doesn't exist")]
+    TemplateDoesntExist,
+
+    /// action changes what all campaigns share and the admin isn't a site admin
+    #[display(fmt = "Only site admins can do this")]
+    SiteAdminRequired,
+
+    /// admin is a member of the campaign, but their role doesn't allow the action
+    #[display(fmt = "Your role on this campaign doesn't allow this")]
+    CampaignPermissionDenied,
+    #[display(fmt = "The campaign's creator is always an owner")]
+    CampaignCreatorRole,
+
+    #[display(fmt = "Results page cursor is invalid")]
+    InvalidResultsCursor,
+    /// date or thread count range is out of bounds or reversed
+    #[display(fmt = "Results filter is invalid")]
+    InvalidResultsFilter,
+
+    #[display(
+        fmt = "Target percentile must be between 0 and 100 and duration a positive number"
+    )]
+    InvalidRecommendationTarget,
+    #[display(fmt = "Campaign has no benchmark results to analyse")]
+    CampaignHasNoResults,
+    #[display(
+        fmt = "Levels need 1 to 20 time budgets, ordered by increasing visitor threshold"
+    )]
+    InvalidLevels,
+
+    #[display(
+        fmt = "Device alias pattern needs a letter or a digit and canonical name must be 1 to 100 characters long"
+    )]
+    InvalidDeviceAlias,
+    #[display(fmt = "A device alias with this pattern already exists")]
+    DeviceAliasExists,
+    #[display(fmt = "Device alias doesn't exist")]
+    DeviceAliasDoesntExist,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -127,6 +227,44 @@ impl ResponseError for ServiceError {
 
             ServiceError::CampaignDoesntExist => StatusCode::NOT_FOUND,
             ServiceError::NotANumber => StatusCode::BAD_REQUEST,
+
+            ServiceError::NoBenches => StatusCode::BAD_REQUEST,
+            ServiceError::DifficultyNotInCampaign => StatusCode::BAD_REQUEST,
+            ServiceError::TooManySamples => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidDuration => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidProof => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidThreadCount => StatusCode::BAD_REQUEST,
+            ServiceError::DeviceInfoTooLong => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidDeviceMemory => StatusCode::BAD_REQUEST,
+
+            ServiceError::SubmissionNotFound => StatusCode::NOT_FOUND,
+
+            ServiceError::CampaignNotStarted => StatusCode::FORBIDDEN,
+            ServiceError::CampaignClosed => StatusCode::FORBIDDEN,
+            ServiceError::InvalidCampaignSchedule => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidResponseLimit => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidRepetitions => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidDifficulties => StatusCode::BAD_REQUEST,
+            ServiceError::CampaignDescriptionTooLong => StatusCode::BAD_REQUEST,
+            ServiceError::CampaignInstructionsTooLong => StatusCode::BAD_REQUEST,
+            ServiceError::SubmissionLimitReached => StatusCode::FORBIDDEN,
+            ServiceError::InvalidIdempotencyKey => StatusCode::BAD_REQUEST,
+            ServiceError::UnknownBenchType => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidBenchTypeName => StatusCode::BAD_REQUEST,
+            ServiceError::BenchTypeExists => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidTemplateName => StatusCode::BAD_REQUEST,
+            ServiceError::TemplateDoesntExist => StatusCode::NOT_FOUND,
+            ServiceError::SiteAdminRequired => StatusCode::FORBIDDEN,
+            ServiceError::CampaignPermissionDenied => StatusCode::FORBIDDEN,
+            ServiceError::CampaignCreatorRole => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidResultsCursor => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidResultsFilter => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidRecommendationTarget => StatusCode::BAD_REQUEST,
+            ServiceError::CampaignHasNoResults => StatusCode::NOT_FOUND,
+            ServiceError::InvalidLevels => StatusCode::BAD_REQUEST,
+            ServiceError::InvalidDeviceAlias => StatusCode::BAD_REQUEST,
+            ServiceError::DeviceAliasExists => StatusCode::BAD_REQUEST,
+            ServiceError::DeviceAliasDoesntExist => StatusCode::NOT_FOUND,
         }
     }
 }
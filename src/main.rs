@@ -31,12 +31,14 @@ mod api;
 mod archive;
 mod data;
 mod errors;
+mod markdown;
 mod pages;
 mod settings;
 mod static_assets;
 #[cfg(test)]
 #[macro_use]
 mod tests;
+mod trash;
 
 pub use crate::data::Data;
 pub use api::v1::ROUTES as V1_API_ROUTES;
@@ -93,6 +95,7 @@ async fn main() -> std::io::Result<()> {
     let arch = archive::Archiver::new(&data.settings);
     let (archive_kiler, archive_job) =
         arch.init_archive_job(data.clone()).await.unwrap();
+    let (purge_killer, purge_job) = trash::init_purge_job(data.clone()).await.unwrap();
 
     let ip = settings.server.get_ip();
     println!("Starting server on: http://{}", ip);
@@ -125,6 +128,8 @@ async fn main() -> std::io::Result<()> {
 
     archive_kiler.send(true).unwrap();
     archive_job.await;
+    purge_killer.send(true).unwrap();
+    purge_job.await;
     Ok(())
 }
 
@@ -0,0 +1,53 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Rendering of admin-provided Markdown, like campaign descriptions, into HTML that
+//! is safe to embed in pages
+use pulldown_cmark::{html, Options, Parser};
+
+/// render `markdown` to HTML. Raw HTML in the input is sanitized, so the output can be
+/// inserted into templates without escaping.
+pub fn render(markdown: &str) -> String {
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES,
+    );
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+    ammonia::clean(&unsafe_html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_rendering_works() {
+        assert_eq!(
+            render("Takes *about* **5 minutes**"),
+            "<p>Takes <em>about</em> <strong>5 minutes</strong></p>\n"
+        );
+        assert_eq!(
+            render("[source](https://example.com)"),
+            "<p><a href=\"https://example.com\" rel=\"noopener noreferrer\">source</a></p>\n"
+        );
+
+        // scripts and event handlers are stripped
+        assert_eq!(render("<script>alert(1)</script>hi"), "hi");
+        assert_eq!(render("<img src=x onerror=alert(1)>"), "<img src=\"x\">");
+        assert!(!render("[x](javascript:alert(1))").contains("javascript"));
+    }
+}
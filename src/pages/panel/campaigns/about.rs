@@ -22,7 +22,11 @@ use actix_web::{web, HttpResponse, Responder};
 use sqlx::types::Uuid;
 use tera::Context;
 
+use crate::api::v1::admin::campaigns::runners::check_accepting_responses;
+use crate::api::v1::bench::runners::get_campaign_info;
+use crate::api::v1::bench::CampaignInfo;
 use crate::errors::ServiceError;
+use crate::markdown;
 use crate::settings::Settings;
 use crate::AppData;
 
@@ -33,6 +37,9 @@ pub struct Intro {
 }
 
 pub const INTRO: TemplateFile = TemplateFile::new("intro", "index.html");
+const CAMPAIGN_KEY: &str = "campaign";
+pub const DESCRIPTION_KEY: &str = "description";
+pub const INSTRUCTIONS_KEY: &str = "instructions";
 
 impl CtxError for Intro {
     fn with_error(&self, e: &ReadableError) -> String {
@@ -42,11 +49,20 @@ impl CtxError for Intro {
 }
 
 impl Intro {
-    pub fn new(settings: &Settings, payload: Option<&str>) -> Self {
+    pub fn new(settings: &Settings, payload: Option<(&str, &CampaignInfo)>) -> Self {
         let ctx = RefCell::new(context(settings, "Campaign Homepage"));
-        if let Some(uuid) = payload {
+        if let Some((uuid, campaign)) = payload {
             let payload = crate::PAGES.panel.campaigns.get_bench_route(uuid);
             ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+            ctx.borrow_mut().insert(CAMPAIGN_KEY, campaign);
+            if let Some(description) = &campaign.description {
+                ctx.borrow_mut()
+                    .insert(DESCRIPTION_KEY, &markdown::render(description));
+            }
+            if let Some(instructions) = &campaign.instructions {
+                ctx.borrow_mut()
+                    .insert(INSTRUCTIONS_KEY, &markdown::render(instructions));
+            }
         }
         Self { ctx }
     }
@@ -68,8 +84,15 @@ pub async fn about(
             Intro::new(&data.settings, None),
             ServiceError::CampaignDoesntExist,
         )),
-        Ok(_) => {
-            let about = Intro::new(&data.settings, Some(&path)).render();
+        Ok(uuid) => {
+            let campaign = get_campaign_info(&uuid, &data)
+                .await
+                .map_err(|e| PageError::new(Intro::new(&data.settings, None), e))?;
+            let page = Intro::new(&data.settings, Some((&path, &campaign)));
+            if let Err(e) = check_accepting_responses(&uuid, None, &data).await {
+                return Err(PageError::new(page, e));
+            }
+            let about = page.render();
             let html = ContentType::html();
             Ok(HttpResponse::Ok().content_type(html).body(about))
         }
@@ -18,8 +18,12 @@ use std::cell::RefCell;
 
 use actix_web::http::header::ContentType;
 use actix_web::{web, HttpResponse, Responder};
+use sqlx::types::Uuid;
 use tera::Context;
 
+use super::about::INSTRUCTIONS_KEY;
+use crate::api::v1::bench::runners::get_campaign_info;
+use crate::markdown;
 use crate::AppData;
 use crate::PAGES;
 
@@ -39,8 +43,12 @@ impl CtxError for Bench {
 }
 
 impl Bench {
-    pub fn new(settings: &Settings) -> Self {
+    pub fn new(settings: &Settings, instructions: Option<&str>) -> Self {
         let ctx = RefCell::new(context(settings, "Benchmark"));
+        if let Some(instructions) = instructions {
+            ctx.borrow_mut()
+                .insert(INSTRUCTIONS_KEY, &markdown::render(instructions));
+        }
         Self { ctx }
     }
 
@@ -55,9 +63,13 @@ impl Bench {
 )]
 pub async fn bench(
     data: AppData,
-    _path: web::Path<uuid::Uuid>,
+    path: web::Path<uuid::Uuid>,
 ) -> PageResult<impl Responder, Bench> {
-    let bench = Bench::new(&data.settings).render();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+    let campaign = get_campaign_info(&uuid, &data)
+        .await
+        .map_err(|e| PageError::new(Bench::new(&data.settings, None), e))?;
+    let bench = Bench::new(&data.settings, campaign.instructions.as_deref()).render();
     let html = ContentType::html();
     Ok(HttpResponse::Ok().content_type(html).body(bench))
 }
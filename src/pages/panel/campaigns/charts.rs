@@ -0,0 +1,751 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Charts of a campaign's results, rendered as SVG on the server so that they show
+//! up without JavaScript and can be downloaded as they are.
+use std::fmt::Write;
+
+use actix_identity::Identity;
+use actix_web::http::header;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Uuid;
+
+use super::results::CampaignResults;
+use crate::api::v1::admin::members::{self, CampaignRole};
+use crate::api::v1::admin::stats::{runners, BenchPoint, CampaignStats, DailyResponses};
+use crate::errors::*;
+use crate::AppData;
+
+pub use super::*;
+
+/// upper bound on the number of responses, most recent first, whose benches are
+/// plotted in [Chart::Scatter]
+pub const MAX_SCATTER_RESPONSES: i64 = 1000;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 360.0;
+const LEFT: f64 = 64.0;
+const RIGHT: f64 = 16.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 48.0;
+const SECONDS_PER_DAY: i64 = 86400;
+/// colours of the submission types' series, reused when there are more types
+const PALETTE: [&str; 4] = ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Chart {
+    /// bench durations by difficulty, with a line through each submission type's
+    /// medians
+    Scatter,
+    /// per difficulty box plots of bench durations: boxes span the 25th to the
+    /// 75th percentile and whiskers the 5th to the 95th
+    BoxPlot,
+    /// number of responses per UTC day
+    Timeline,
+}
+
+impl ToString for Chart {
+    fn to_string(&self) -> String {
+        let s = serde_json::to_string(&self).unwrap();
+        (&s[1..(s.len() - 1)]).to_string()
+    }
+}
+
+impl Chart {
+    pub const ALL: [Chart; 3] = [Chart::Scatter, Chart::BoxPlot, Chart::Timeline];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Chart::Scatter => "Bench duration by difficulty",
+            Chart::BoxPlot => "Bench duration distribution by difficulty",
+            Chart::Timeline => "Responses per day",
+        }
+    }
+
+    pub fn render(&self, data: &ChartData) -> String {
+        match self {
+            Chart::Scatter => scatter(self.title(), &data.stats, &data.points),
+            Chart::BoxPlot => box_plot(self.title(), &data.stats),
+            Chart::Timeline => timeline(self.title(), &data.days),
+        }
+    }
+}
+
+/// everything that the charts plot, excluded responses left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartData {
+    pub stats: CampaignStats,
+    pub points: Vec<BenchPoint>,
+    pub days: Vec<DailyResponses>,
+}
+
+impl ChartData {
+    /// `stats` is campaign `uuid`'s summary, which callers usually already have
+    pub async fn fetch(
+        uuid: &Uuid,
+        stats: CampaignStats,
+        data: &AppData,
+    ) -> ServiceResult<Self> {
+        Ok(Self {
+            stats,
+            points: runners::bench_points(uuid, MAX_SCATTER_RESPONSES, data).await?,
+            days: runners::daily_responses(uuid, data).await?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderedChart {
+    pub title: String,
+    pub svg: String,
+    pub download: String,
+}
+
+/// all charts of campaign `uuid`, for the results page
+pub fn render_all(uuid: &Uuid, data: &ChartData) -> Vec<RenderedChart> {
+    let campaign_id = uuid.to_string();
+    Chart::ALL
+        .iter()
+        .map(|chart| RenderedChart {
+            title: chart.title().into(),
+            svg: chart.render(data),
+            download: PAGES
+                .panel
+                .campaigns
+                .get_chart_route(&campaign_id, &chart.to_string()),
+        })
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Ticks from 0 to at least `max`, spaced by 1, 2 or 5 times a power of ten and
+/// by no less than `min_step`
+fn ticks(max: f64, min_step: f64) -> Vec<f64> {
+    let max = if max > 0.0 && max.is_finite() {
+        max
+    } else {
+        1.0
+    };
+    let rough = max / 5.0;
+    let magnitude = 10_f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(rough)
+        .max(min_step);
+    let count = (max / step - 1e-9).ceil().max(1.0) as usize;
+    (0..=count).map(|i| i as f64 * step).collect()
+}
+
+fn format_tick(v: f64) -> String {
+    let (v, unit) = if v >= 1e6 {
+        (v / 1e6, "M")
+    } else if v >= 1e3 {
+        (v / 1e3, "k")
+    } else {
+        (v, "")
+    };
+    format!("{}{unit}", (v * 100.0).round() / 100.0)
+}
+
+/// maps `[0, max]` onto `[from, to]` pixels
+struct Axis {
+    max: f64,
+    from: f64,
+    to: f64,
+}
+
+impl Axis {
+    fn x(max: f64) -> Self {
+        Self {
+            max,
+            from: LEFT,
+            to: WIDTH - RIGHT,
+        }
+    }
+
+    fn y(max: f64) -> Self {
+        Self {
+            max,
+            from: HEIGHT - BOTTOM,
+            to: TOP,
+        }
+    }
+
+    fn pos(&self, v: f64) -> f64 {
+        self.from + (self.to - self.from) * v / self.max
+    }
+}
+
+struct Svg(String);
+
+impl Svg {
+    fn new(title: &str) -> Self {
+        let mut svg = String::new();
+        write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {HEIGHT}" width="{WIDTH}" height="{HEIGHT}" font-family="sans-serif" font-size="12">"#
+        )
+        .unwrap();
+        write!(
+            svg,
+            r#"<title>{title}</title><rect width="{WIDTH}" height="{HEIGHT}" fill="white"/>"#,
+            title = escape(title)
+        )
+        .unwrap();
+        Self(svg)
+    }
+
+    /// gridlines, axes and their labels; ticks are (position, label) pairs
+    fn frame(
+        &mut self,
+        x_label: &str,
+        y_label: &str,
+        x_ticks: &[(f64, String)],
+        y_ticks: &[(f64, String)],
+    ) {
+        let svg = &mut self.0;
+        let (left, right, top, bottom) = (LEFT, WIDTH - RIGHT, TOP, HEIGHT - BOTTOM);
+        for (y, label) in y_ticks {
+            write!(
+                svg,
+                r##"<line x1="{left}" y1="{y:.1}" x2="{right}" y2="{y:.1}" stroke="#ddd"/><text x="{x}" y="{y:.1}" text-anchor="end" dominant-baseline="middle">{label}</text>"##,
+                x = left - 6.0,
+            )
+            .unwrap();
+        }
+        for (x, label) in x_ticks {
+            write!(
+                svg,
+                r##"<line x1="{x:.1}" y1="{bottom}" x2="{x:.1}" y2="{y2}" stroke="#000"/><text x="{x:.1}" y="{y}" text-anchor="middle">{label}</text>"##,
+                y2 = bottom + 4.0,
+                y = bottom + 18.0,
+            )
+            .unwrap();
+        }
+        write!(
+            svg,
+            r##"<path d="M{left} {top}V{bottom}H{right}" fill="none" stroke="#000"/>"##
+        )
+        .unwrap();
+        write!(
+            svg,
+            r#"<text x="{x}" y="{y}" text-anchor="middle">{x_label}</text>"#,
+            x = (left + right) / 2.0,
+            y = HEIGHT - 8.0,
+            x_label = escape(x_label),
+        )
+        .unwrap();
+        write!(
+            svg,
+            r#"<text x="16" y="{y}" text-anchor="middle" transform="rotate(-90 16 {y})">{y_label}</text>"#,
+            y = (top + bottom) / 2.0,
+            y_label = escape(y_label),
+        )
+        .unwrap();
+    }
+
+    /// a swatch and the name of each submission type, above the plot
+    fn legend(&mut self, types: &[String]) {
+        let mut x = LEFT;
+        for (i, name) in types.iter().enumerate() {
+            write!(
+                self.0,
+                r#"<rect x="{x}" y="12" width="12" height="12" fill="{colour}"/><text x="{text_x}" y="22">{name}</text>"#,
+                colour = PALETTE[i % PALETTE.len()],
+                text_x = x + 16.0,
+                name = escape(name),
+            )
+            .unwrap();
+            x += 32.0 + 7.0 * name.chars().count() as f64;
+        }
+    }
+
+    fn empty(&mut self) {
+        write!(
+            self.0,
+            r#"<text x="{x}" y="{y}" text-anchor="middle">No responses yet</text>"#,
+            x = WIDTH / 2.0,
+            y = HEIGHT / 2.0,
+        )
+        .unwrap();
+    }
+
+    fn finish(mut self) -> String {
+        self.0.push_str("</svg>");
+        self.0
+    }
+}
+
+/// ticks of `axis` as (position, label) pairs
+fn axis_ticks(axis: &Axis, ticks: &[f64]) -> Vec<(f64, String)> {
+    ticks
+        .iter()
+        .map(|t| (axis.pos(*t), format_tick(*t)))
+        .collect()
+}
+
+/// submission types in the order of `stats`, followed by any others in `points`
+fn submission_types(stats: &CampaignStats, points: &[BenchPoint]) -> Vec<String> {
+    let mut types: Vec<String> = stats
+        .submission_types
+        .iter()
+        .map(|t| t.submission_type.to_string())
+        .collect();
+    for p in points.iter() {
+        let name = p.submission_type.to_string();
+        if !types.contains(&name) {
+            types.push(name);
+        }
+    }
+    types
+}
+
+fn colour(types: &[String], name: &str) -> &'static str {
+    let i = types.iter().position(|t| t == name).unwrap_or_default();
+    PALETTE[i % PALETTE.len()]
+}
+
+fn scatter(title: &str, stats: &CampaignStats, points: &[BenchPoint]) -> String {
+    let mut svg = Svg::new(title);
+    let types = submission_types(stats, points);
+    if points.is_empty() {
+        svg.frame("Difficulty", "Duration (ms)", &[], &[]);
+        svg.empty();
+        return svg.finish();
+    }
+
+    let difficulties = stats
+        .submission_types
+        .iter()
+        .flat_map(|t| t.difficulties.iter());
+    let x_max = points
+        .iter()
+        .map(|p| p.difficulty as f64)
+        .chain(difficulties.clone().map(|d| d.difficulty as f64))
+        .fold(0.0, f64::max);
+    let y_max = points
+        .iter()
+        .map(|p| p.duration as f64)
+        .chain(difficulties.map(|d| d.median))
+        .fold(0.0, f64::max);
+    let x_ticks = ticks(x_max, 0.0);
+    let y_ticks = ticks(y_max, 0.0);
+    let x = Axis::x(*x_ticks.last().unwrap());
+    let y = Axis::y(*y_ticks.last().unwrap());
+    svg.frame(
+        "Difficulty",
+        "Duration (ms)",
+        &axis_ticks(&x, &x_ticks),
+        &axis_ticks(&y, &y_ticks),
+    );
+
+    for p in points.iter() {
+        write!(
+            svg.0,
+            r#"<circle cx="{cx:.1}" cy="{cy:.1}" r="2" fill="{colour}" fill-opacity="0.4"/>"#,
+            cx = x.pos(p.difficulty as f64),
+            cy = y.pos(p.duration as f64),
+            colour = colour(&types, &p.submission_type.to_string()),
+        )
+        .unwrap();
+    }
+    for t in stats.submission_types.iter() {
+        let line: Vec<String> = t
+            .difficulties
+            .iter()
+            .map(|d| format!("{:.1},{:.1}", x.pos(d.difficulty as f64), y.pos(d.median)))
+            .collect();
+        write!(
+            svg.0,
+            r#"<polyline points="{}" fill="none" stroke="{colour}" stroke-width="2"/>"#,
+            line.join(" "),
+            colour = colour(&types, &t.submission_type.to_string()),
+        )
+        .unwrap();
+    }
+    svg.legend(&types);
+    svg.finish()
+}
+
+fn box_plot(title: &str, stats: &CampaignStats) -> String {
+    let mut svg = Svg::new(title);
+    let types = submission_types(stats, &[]);
+    let mut difficulties: Vec<i32> = stats
+        .submission_types
+        .iter()
+        .flat_map(|t| t.difficulties.iter().map(|d| d.difficulty))
+        .collect();
+    difficulties.sort_unstable();
+    difficulties.dedup();
+    if difficulties.is_empty() {
+        svg.frame("Difficulty", "Duration (ms)", &[], &[]);
+        svg.empty();
+        return svg.finish();
+    }
+
+    let y_max = stats
+        .submission_types
+        .iter()
+        .flat_map(|t| t.difficulties.iter().map(|d| d.p95))
+        .fold(0.0, f64::max);
+    let y_ticks = ticks(y_max, 0.0);
+    let y = Axis::y(*y_ticks.last().unwrap());
+    let band = (WIDTH - RIGHT - LEFT) / difficulties.len() as f64;
+    let band_centre = |i: usize| LEFT + band * (i as f64 + 0.5);
+    let x_ticks: Vec<(f64, String)> = difficulties
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (band_centre(i), format_tick(*d as f64)))
+        .collect();
+    svg.frame(
+        "Difficulty",
+        "Duration (ms)",
+        &x_ticks,
+        &axis_ticks(&y, &y_ticks),
+    );
+
+    let box_width = band * 0.8 / types.len() as f64;
+    for (t_index, t) in stats.submission_types.iter().enumerate() {
+        let colour = PALETTE[t_index % PALETTE.len()];
+        for d in t.difficulties.iter() {
+            let i = difficulties.binary_search(&d.difficulty).unwrap();
+            let left = band_centre(i) - band * 0.4 + box_width * t_index as f64;
+            let centre = left + box_width / 2.0;
+            let right = left + box_width;
+            write!(
+                svg.0,
+                r#"<path d="M{centre:.1} {p5:.1}V{p25:.1}M{centre:.1} {p75:.1}V{p95:.1}M{cap_left:.1} {p5:.1}H{cap_right:.1}M{cap_left:.1} {p95:.1}H{cap_right:.1}" stroke="{colour}"/>"#,
+                p5 = y.pos(d.p5),
+                p25 = y.pos(d.p25),
+                p75 = y.pos(d.p75),
+                p95 = y.pos(d.p95),
+                cap_left = centre - box_width / 4.0,
+                cap_right = centre + box_width / 4.0,
+            )
+            .unwrap();
+            write!(
+                svg.0,
+                r#"<rect x="{left:.1}" y="{top:.1}" width="{width:.1}" height="{height:.1}" fill="{colour}" fill-opacity="0.4" stroke="{colour}"/><path d="M{left:.1} {median:.1}H{right:.1}" stroke="{colour}" stroke-width="2"/>"#,
+                top = y.pos(d.p75),
+                width = box_width,
+                height = y.pos(d.p25) - y.pos(d.p75),
+                median = y.pos(d.median),
+            )
+            .unwrap();
+        }
+    }
+    svg.legend(&types);
+    svg.finish()
+}
+
+fn timeline(title: &str, days: &[DailyResponses]) -> String {
+    let mut svg = Svg::new(title);
+    let (first, last) = match (days.first(), days.last()) {
+        (Some(first), Some(last)) => (first.day, last.day),
+        _ => {
+            svg.frame("Day (UTC)", "Responses", &[], &[]);
+            svg.empty();
+            return svg.finish();
+        }
+    };
+
+    let day_count = ((last - first) / SECONDS_PER_DAY + 1) as usize;
+    let y_max = days.iter().map(|d| d.responses).max().unwrap_or_default();
+    let y_ticks = ticks(y_max as f64, 1.0);
+    let y = Axis::y(*y_ticks.last().unwrap());
+    let band = (WIDTH - RIGHT - LEFT) / day_count as f64;
+    let band_centre = |i: usize| LEFT + band * (i as f64 + 0.5);
+
+    // at most 6 date labels, so that they don't overlap
+    let label_every = day_count.div_ceil(6);
+    let x_ticks: Vec<(f64, String)> = (0..day_count)
+        .step_by(label_every)
+        .map(|i| {
+            let day =
+                OffsetDateTime::from_unix_timestamp(first + i as i64 * SECONDS_PER_DAY)
+                    .map(|t| t.date().to_string())
+                    .unwrap_or_default();
+            (band_centre(i), day)
+        })
+        .collect();
+    svg.frame(
+        "Day (UTC)",
+        "Responses",
+        &x_ticks,
+        &axis_ticks(&y, &y_ticks),
+    );
+
+    let bar_width = (band * 0.8).max(1.0);
+    for d in days.iter() {
+        let i = ((d.day - first) / SECONDS_PER_DAY) as usize;
+        let top = y.pos(d.responses as f64);
+        write!(
+            svg.0,
+            r#"<rect x="{x:.1}" y="{top:.1}" width="{bar_width:.1}" height="{height:.1}" fill="{colour}"><title>{responses}</title></rect>"#,
+            x = band_centre(i) - bar_width / 2.0,
+            height = HEIGHT - BOTTOM - top,
+            colour = PALETTE[0],
+            responses = d.responses,
+        )
+        .unwrap();
+    }
+    svg.finish()
+}
+
+async fn get_svg(
+    username: &str,
+    uuid: &Uuid,
+    chart: Chart,
+    data: &AppData,
+) -> ServiceResult<String> {
+    members::runners::require_role(username, uuid, CampaignRole::Viewer, data).await?;
+    let stats = runners::summary(uuid, data).await?;
+    let chart_data = ChartData::fetch(uuid, stats, data).await?;
+    Ok(chart.render(&chart_data))
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "PAGES.panel.campaigns.chart",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn download_chart(
+    id: Identity,
+    path: web::Path<(uuid::Uuid, Chart)>,
+    data: AppData,
+) -> PageResult<impl Responder, CampaignResults> {
+    let username = id.identity().unwrap();
+    let (uuid, chart) = path.into_inner();
+    let uuid = Uuid::parse_str(&uuid.to_string()).unwrap();
+
+    let svg = get_svg(&username, &uuid, chart, &data)
+        .await
+        .map_err(|e| PageError::new(CampaignResults::new(&data.settings, None), e))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/svg+xml")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}-{uuid}.svg\"", chart.to_string()),
+        ))
+        .body(svg))
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(download_chart);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+
+    use super::*;
+    use crate::api::v1::admin::stats::{DifficultyStats, SubmissionTypeStats};
+    use crate::api::v1::bench::{Submission, SubmissionType};
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+
+    fn difficulty(difficulty: i32, median: f64) -> DifficultyStats {
+        DifficultyStats {
+            difficulty,
+            count: 2,
+            mean: median,
+            p5: median / 2.0,
+            p25: median * 0.75,
+            median,
+            p75: median * 1.25,
+            p90: median * 1.4,
+            p95: median * 1.5,
+            p99: median * 1.6,
+            min: (median / 2.0) as f32,
+            max: (median * 2.0) as f32,
+        }
+    }
+
+    #[test]
+    fn ticks_work() {
+        assert_eq!(ticks(9.0, 0.0), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(ticks(10.0, 0.0), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(ticks(1.0, 1.0), vec![0.0, 1.0]);
+        assert_eq!(ticks(0.0, 0.0), ticks(1.0, 0.0));
+        assert_eq!(format_tick(0.6000000000000001), "0.6");
+        assert_eq!(format_tick(2500.0), "2.5k");
+        assert_eq!(format_tick(4_000_000.0), "4M");
+    }
+
+    #[test]
+    fn charts_render() {
+        let name = "<js>".to_string();
+        let stats = CampaignStats {
+            responses: 2,
+            first_submitted_at: Some(0),
+            last_submitted_at: Some(3 * SECONDS_PER_DAY),
+            submission_types: vec![SubmissionTypeStats {
+                submission_type: SubmissionType::new(name.clone()),
+                responses: 2,
+                first_submitted_at: 0,
+                last_submitted_at: 3 * SECONDS_PER_DAY,
+                difficulties: vec![difficulty(100, 10.0), difficulty(1000, 100.0)],
+            }],
+            devices: Vec::new(),
+        };
+        let points = [(100, 5.0), (1000, 50.0), (100, 15.0), (1000, 150.0)]
+            .iter()
+            .map(|(difficulty, duration)| BenchPoint {
+                submission_type: SubmissionType::new(name.clone()),
+                difficulty: *difficulty,
+                duration: *duration,
+            })
+            .collect();
+        let days = vec![
+            DailyResponses {
+                day: 0,
+                responses: 1,
+            },
+            DailyResponses {
+                day: 3 * SECONDS_PER_DAY,
+                responses: 1,
+            },
+        ];
+        let data = ChartData {
+            stats,
+            points,
+            days,
+        };
+
+        let scatter = Chart::Scatter.render(&data);
+        assert!(scatter.starts_with("<svg"));
+        assert!(scatter.ends_with("</svg>"));
+        assert_eq!(scatter.matches("<circle").count(), 4);
+        assert_eq!(scatter.matches("<polyline").count(), 1);
+        assert!(scatter.contains("&lt;js&gt;"));
+        assert!(!scatter.contains("<js>"));
+
+        let box_plot = Chart::BoxPlot.render(&data);
+        // a box for each difficulty, besides the background and the legend swatch
+        assert_eq!(box_plot.matches("<rect").count(), 2 + 2);
+
+        let timeline = Chart::Timeline.render(&data);
+        assert_eq!(timeline.matches("<title>1</title>").count(), 2);
+        assert!(timeline.contains("1970-01-01"));
+
+        let empty = ChartData {
+            stats: CampaignStats {
+                responses: 0,
+                first_submitted_at: None,
+                last_submitted_at: None,
+                submission_types: Vec::new(),
+                devices: Vec::new(),
+            },
+            points: Vec::new(),
+            days: Vec::new(),
+        };
+        for chart in Chart::ALL {
+            assert!(chart.render(&empty).contains("No responses yet"));
+        }
+    }
+
+    #[actix_rt::test]
+    async fn chart_downloads_work() {
+        const NAME: &str = "chartdownloadsuser";
+        const EMAIL: &str = "chartdownloadsuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const OTHER: &str = "chartdownloadsother";
+        const OTHER_EMAIL: &str = "chartdownloadsother@aaa.com";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        delete_user(OTHER, &data).await;
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let (_, _, signin_resp) =
+            register_and_signin(OTHER, OTHER_EMAIL, PASSWORD).await;
+        let other_cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: NAME.into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        for chart in Chart::ALL {
+            let route = PAGES
+                .panel
+                .campaigns
+                .get_chart_route(&campaign.campaign_id, &chart.to_string());
+            let resp = get_request!(&app, &route, cookies.clone());
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(
+                resp.headers().get(header::CONTENT_TYPE).unwrap(),
+                "image/svg+xml"
+            );
+            assert!(resp
+                .headers()
+                .get(header::CONTENT_DISPOSITION)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with("attachment"));
+            let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+            assert!(body.starts_with("<svg"));
+
+            let resp = get_request!(&app, &route, other_cookies.clone());
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+
+        let results_route = PAGES
+            .panel
+            .campaigns
+            .get_results_route(&campaign.campaign_id, None);
+        let resp = get_request!(&app, &results_route, cookies);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert_eq!(body.matches("<svg").count(), Chart::ALL.len());
+        assert!(body.contains(
+            &PAGES
+                .panel
+                .campaigns
+                .get_chart_route(&campaign.campaign_id, "box_plot")
+        ));
+    }
+}
@@ -20,6 +20,7 @@ use sqlx::types::Uuid;
 
 use crate::api::v1::admin::auth::runners::{login_runner, Login, Password};
 use crate::api::v1::admin::campaigns::runners;
+use crate::api::v1::admin::members::{self, CampaignRole};
 use crate::errors::*;
 //use crate::AppData;
 //use crate::PAGES;
@@ -80,16 +81,14 @@ async fn get_title(
     struct Name {
         name: String,
     }
+    members::runners::require_role(username, uuid, CampaignRole::Owner, data).await?;
     let campaign = sqlx::query_as!(
         Name,
         "SELECT name 
      FROM survey_campaigns
      WHERE 
-         id = $1
-     AND
-        user_id = (SELECT ID from survey_admins WHERE name = $2)",
+         id = $1",
         &uuid,
-        &username
     )
     .fetch_one(&data.db)
     .await?;
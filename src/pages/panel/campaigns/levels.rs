@@ -0,0 +1,322 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use actix_identity::Identity;
+use actix_web::http::header::{self, ContentType};
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use tera::Context;
+
+use super::recommend::DEFAULT_PERCENTILE;
+use crate::api::v1::admin::bench_types;
+use crate::api::v1::admin::members::{self, CampaignRole};
+use crate::api::v1::admin::stats::{runners, seconds, LevelBudget, LevelsTarget};
+use crate::api::v1::bench::SubmissionType;
+use crate::errors::*;
+use crate::AppData;
+
+pub use super::*;
+
+pub struct Levels {
+    ctx: RefCell<Context>,
+}
+
+pub const LEVELS: TemplateFile =
+    TemplateFile::new("campaign_levels", "panel/campaigns/levels.html");
+
+impl CtxError for Levels {
+    fn with_error(&self, e: &ReadableError) -> String {
+        self.ctx.borrow_mut().insert(ERROR_KEY, e);
+        self.render()
+    }
+}
+
+/// budgets pre-filled in the form: visitor threshold and seconds, one level a line
+const DEFAULT_BUDGETS: &str = "50 0.5\n500 1\n5000 2\n50000 5";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelsForm {
+    pub percentile: String,
+    pub bench_type: String,
+    /// a "visitor_threshold max_duration" pair per line
+    pub budgets: String,
+}
+
+impl Default for LevelsForm {
+    fn default() -> Self {
+        Self {
+            percentile: DEFAULT_PERCENTILE.to_string(),
+            bench_type: String::default(),
+            budgets: DEFAULT_BUDGETS.into(),
+        }
+    }
+}
+
+impl LevelsForm {
+    fn parse(&self) -> ServiceResult<LevelsTarget> {
+        let percentile = self
+            .percentile
+            .trim()
+            .parse()
+            .map_err(|_| ServiceError::InvalidRecommendationTarget)?;
+        let bench_type = match self.bench_type.trim() {
+            "" => None,
+            t => Some(SubmissionType::new(t)),
+        };
+
+        let mut budgets = Vec::new();
+        for line in self.budgets.lines().filter(|l| !l.trim().is_empty()) {
+            let mut fields = line.split_whitespace();
+            let budget = match (fields.next(), fields.next(), fields.next()) {
+                (Some(visitor_threshold), Some(max_duration), None) => visitor_threshold
+                    .parse()
+                    .ok()
+                    .zip(max_duration.parse().ok()),
+                _ => None,
+            };
+            let (visitor_threshold, max_duration) =
+                budget.ok_or(ServiceError::InvalidLevels)?;
+            budgets.push(LevelBudget {
+                visitor_threshold,
+                max_duration: seconds::to_millis(max_duration),
+            });
+        }
+
+        Ok(LevelsTarget {
+            percentile,
+            bench_type,
+            budgets,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelsPagePayload {
+    pub form: LevelsForm,
+    pub bench_types: Vec<String>,
+    pub recommend: String,
+}
+
+impl Levels {
+    pub fn new(settings: &Settings, payload: Option<LevelsPagePayload>) -> Self {
+        let ctx = RefCell::new(context(settings, "mCaptcha levels"));
+        if let Some(payload) = payload {
+            ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+        }
+        Self { ctx }
+    }
+
+    pub fn render(&self) -> String {
+        TEMPLATES.render(LEVELS.name, &self.ctx.borrow()).unwrap()
+    }
+}
+
+async fn get_payload(
+    username: &str,
+    uuid: &Uuid,
+    form: LevelsForm,
+    data: &AppData,
+) -> ServiceResult<LevelsPagePayload> {
+    members::runners::require_role(username, uuid, CampaignRole::Viewer, data).await?;
+    let bench_types = bench_types::runners::list(data).await?;
+    Ok(LevelsPagePayload {
+        form,
+        bench_types: bench_types
+            .into_iter()
+            .map(|t| t.name.to_string())
+            .collect(),
+        recommend: PAGES.panel.campaigns.get_recommend_route(&uuid.to_string()),
+    })
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "PAGES.panel.campaigns.levels",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn levels(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    data: AppData,
+) -> PageResult<impl Responder, Levels> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    let payload = get_payload(&username, &uuid, LevelsForm::default(), &data)
+        .await
+        .map_err(|e| PageError::new(Levels::new(&data.settings, None), e))?;
+
+    let page = Levels::new(&data.settings, Some(payload)).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+/// levels JSON, as a download
+#[actix_web_codegen_const_routes::post(
+    path = "PAGES.panel.campaigns.levels",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn download_levels(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    payload: web::Form<LevelsForm>,
+    data: AppData,
+) -> PageResult<impl Responder, Levels> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+    let form = payload.into_inner();
+
+    let res = match members::runners::require_role(
+        &username,
+        &uuid,
+        CampaignRole::Viewer,
+        &data,
+    )
+    .await
+    {
+        Ok(_) => match form.parse() {
+            Ok(target) => runners::levels(&uuid, &target, &data).await,
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+
+    match res {
+        Ok(levels) => Ok(HttpResponse::Ok()
+            .content_type(ContentType::json())
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"mcaptcha-levels-{uuid}.json\""),
+            ))
+            .body(serde_json::to_string_pretty(&levels).unwrap())),
+        Err(e) => {
+            let payload = get_payload(&username, &uuid, form, &data).await.ok();
+            Err(PageError::new(Levels::new(&data.settings, payload), e))
+        }
+    }
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(levels);
+    cfg.service(download_levels);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use actix_web::ResponseError;
+
+    use super::*;
+    use crate::api::v1::admin::stats::{Level, LevelsConfig};
+    use crate::api::v1::bench::Submission;
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+
+    #[actix_rt::test]
+    async fn levels_page_works() {
+        const NAME: &str = "levelspageuser";
+        const EMAIL: &str = "levelspageuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const CAMPAIGN_NAME: &str = "levelspageusercampaign";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+
+        let campaign =
+            create_new_campaign(CAMPAIGN_NAME, data.clone(), cookies.clone()).await;
+        let route = PAGES
+            .panel
+            .campaigns
+            .get_levels_route(&campaign.campaign_id);
+
+        let resp = get_request!(&app, &route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "levelspageuser".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &SLOW_BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        let form = LevelsForm {
+            percentile: "95".into(),
+            bench_type: "".into(),
+            budgets: "50 3\n\n500 4.5\n".into(),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&form, &route, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp
+            .headers()
+            .get(header::CONTENT_DISPOSITION)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("attachment"));
+        let config: LevelsConfig = test::read_body_json(resp).await;
+        assert_eq!(
+            config.levels,
+            vec![
+                Level {
+                    visitor_threshold: 50,
+                    difficulty_factor: 3
+                },
+                Level {
+                    visitor_threshold: 500,
+                    difficulty_factor: 4
+                }
+            ]
+        );
+
+        let form = LevelsForm {
+            budgets: "50 3 1".into(),
+            ..form
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&form, &route, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::InvalidLevels.status_code());
+    }
+}
@@ -0,0 +1,285 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use actix_identity::Identity;
+use actix_web::http::header;
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use tera::Context;
+
+use crate::api::v1::admin::members::{
+    runners, AddMember, CampaignMember, CampaignRole, RemoveMember,
+};
+use crate::errors::*;
+use crate::AppData;
+
+pub use super::*;
+
+pub struct CampaignMembers {
+    ctx: RefCell<Context>,
+}
+
+pub const CAMPAIGN_MEMBERS: TemplateFile =
+    TemplateFile::new("campaign_members", "panel/campaigns/members.html");
+
+impl CtxError for CampaignMembers {
+    fn with_error(&self, e: &ReadableError) -> String {
+        self.ctx.borrow_mut().insert(ERROR_KEY, e);
+        self.render()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MembersPagePayload {
+    pub members: Vec<CampaignMember>,
+    /// `Some` when the admin can invite and remove members
+    pub add_url: Option<String>,
+    pub remove_url: Option<String>,
+}
+
+impl CampaignMembers {
+    pub fn new(settings: &Settings, payload: Option<MembersPagePayload>) -> Self {
+        let ctx = RefCell::new(context(settings, "Members"));
+        if let Some(payload) = payload {
+            ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+        }
+        Self { ctx }
+    }
+
+    pub fn render(&self) -> String {
+        TEMPLATES
+            .render(CAMPAIGN_MEMBERS.name, &self.ctx.borrow())
+            .unwrap()
+    }
+}
+
+async fn get_payload(
+    username: &str,
+    uuid: &Uuid,
+    data: &AppData,
+) -> ServiceResult<MembersPagePayload> {
+    let role = runners::require_role(username, uuid, CampaignRole::Viewer, data).await?;
+    let members = runners::list(uuid, data).await?;
+    let campaign_id = uuid.to_string();
+    let (add_url, remove_url) = if role == CampaignRole::Owner {
+        (
+            Some(PAGES.panel.campaigns.get_members_route(&campaign_id)),
+            Some(PAGES.panel.campaigns.get_remove_member_route(&campaign_id)),
+        )
+    } else {
+        (None, None)
+    };
+    Ok(MembersPagePayload {
+        members,
+        add_url,
+        remove_url,
+    })
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "PAGES.panel.campaigns.members",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn members(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    data: AppData,
+) -> PageResult<impl Responder, CampaignMembers> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    let payload = get_payload(&username, &uuid, &data)
+        .await
+        .map_err(|e| PageError::new(CampaignMembers::new(&data.settings, None), e))?;
+
+    let page = CampaignMembers::new(&data.settings, Some(payload)).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "PAGES.panel.campaigns.members",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn add_member(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    payload: web::Form<AddMember>,
+    data: AppData,
+) -> PageResult<impl Responder, CampaignMembers> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    let res = match runners::require_role(&username, &uuid, CampaignRole::Owner, &data)
+        .await
+    {
+        Ok(_) => runners::add(&uuid, &payload, &data).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        let payload = get_payload(&username, &uuid, &data).await.ok();
+        return Err(PageError::new(
+            CampaignMembers::new(&data.settings, payload),
+            e,
+        ));
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((
+            header::LOCATION,
+            PAGES.panel.campaigns.get_members_route(&uuid.to_string()),
+        ))
+        .finish())
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "PAGES.panel.campaigns.remove_member",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn remove_member(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    payload: web::Form<RemoveMember>,
+    data: AppData,
+) -> PageResult<impl Responder, CampaignMembers> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    let res = match runners::require_role(&username, &uuid, CampaignRole::Owner, &data)
+        .await
+    {
+        Ok(_) => runners::remove(&uuid, &payload.username, &data).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        let payload = get_payload(&username, &uuid, &data).await.ok();
+        return Err(PageError::new(
+            CampaignMembers::new(&data.settings, payload),
+            e,
+        ));
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((
+            header::LOCATION,
+            PAGES.panel.campaigns.get_members_route(&uuid.to_string()),
+        ))
+        .finish())
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(members);
+    cfg.service(add_member);
+    cfg.service(remove_member);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+
+    use super::*;
+
+    use crate::tests::*;
+    use crate::*;
+    use actix_web::http::StatusCode;
+
+    #[actix_rt::test]
+    async fn campaign_members_page_works() {
+        const NAME: &str = "memberspageuser";
+        const EMAIL: &str = "memberspageuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const MEMBER: &str = "memberspagemember";
+        const MEMBER_EMAIL: &str = "memberspagemember@aaa.com";
+        const CAMPAIGN_NAME: &str = "memberspageusercampaign";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        delete_user(MEMBER, &data).await;
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let (_, _, signin_resp) =
+            register_and_signin(MEMBER, MEMBER_EMAIL, PASSWORD).await;
+        let member_cookies = get_cookie!(signin_resp);
+
+        let campaign =
+            create_new_campaign(CAMPAIGN_NAME, data.clone(), cookies.clone()).await;
+        let members_route = PAGES
+            .panel
+            .campaigns
+            .get_members_route(&campaign.campaign_id);
+
+        let resp = get_request!(&app, &members_route, member_cookies.clone());
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let add = AddMember {
+            user: MEMBER_EMAIL.into(),
+            role: CampaignRole::Viewer,
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, &members_route, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(
+            resp.headers().get(header::LOCATION).unwrap(),
+            &members_route
+        );
+
+        let resp = get_request!(&app, &members_route, member_cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(MEMBER));
+
+        // viewers can't manage members
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, &members_route, FORM)
+                .cookie(member_cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let remove = RemoveMember {
+            username: MEMBER.into(),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &remove,
+                &PAGES
+                    .panel
+                    .campaigns
+                    .get_remove_member_route(&campaign.campaign_id),
+                FORM
+            )
+            .cookie(cookies.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        let resp = get_request!(&app, &members_route, member_cookies);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
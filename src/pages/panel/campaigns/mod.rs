@@ -25,16 +25,23 @@ use crate::api::v1::admin::campaigns::ResultsPage;
 use crate::api::v1::admin::campaigns::{
     runners::list_campaign_runner, ListCampaignResp,
 };
-use crate::api::v1::bench::SubmissionType;
+use crate::api::v1::admin::members::CampaignRole;
 use crate::pages::errors::*;
 use crate::AppData;
 use crate::Settings;
 
 pub mod about;
 pub mod bench;
+pub mod charts;
 pub mod delete;
+pub mod levels;
+pub mod members;
 pub mod new;
+pub mod receipt;
+pub mod recommend;
 pub mod results;
+pub mod review;
+pub mod trash;
 
 pub use super::{context, Footer, TemplateFile, PAGES, PAYLOAD_KEY, TEMPLATES};
 
@@ -46,7 +53,13 @@ pub fn register_templates(t: &mut tera::Tera) {
         new::NEW_CAMPAIGN_FORM,
         bench::BENCH,
         delete::SUDO_DELETE,
+        levels::LEVELS,
+        members::CAMPAIGN_MEMBERS,
         results::CAMPAIGN_RESULTS,
+        receipt::RECEIPT,
+        recommend::RECOMMEND,
+        review::REVIEW_QUEUE,
+        trash::TRASH,
     ]
     .iter()
     {
@@ -66,7 +79,17 @@ pub mod routes {
         pub about: &'static str,
         pub bench: &'static str,
         pub delete: &'static str,
+        pub members: &'static str,
+        pub remove_member: &'static str,
+        pub trash: &'static str,
+        pub restore: &'static str,
         pub results: &'static str,
+        pub recommend: &'static str,
+        pub levels: &'static str,
+        pub review: &'static str,
+        pub chart: &'static str,
+        pub receipt: &'static str,
+        pub delete_submission: &'static str,
     }
 
     impl Campaigns {
@@ -77,7 +100,17 @@ pub mod routes {
                 about: "/survey/campaigns/{uuid}/about",
                 bench: "/survey/campaigns/{uuid}/bench",
                 delete: "/admin/campaigns/{uuid}/delete",
+                members: "/admin/campaigns/{uuid}/members",
+                remove_member: "/admin/campaigns/{uuid}/members/remove",
+                trash: "/admin/campaigns/trash",
+                restore: "/admin/campaigns/{uuid}/restore",
                 results: "/admin/campaigns/{uuid}/results",
+                recommend: "/admin/campaigns/{uuid}/recommend",
+                levels: "/admin/campaigns/{uuid}/levels",
+                review: "/admin/campaigns/{uuid}/review",
+                chart: "/admin/campaigns/{uuid}/charts/{chart}",
+                receipt: "/survey/receipt",
+                delete_submission: "/survey/receipt/delete",
             }
         }
 
@@ -85,6 +118,36 @@ pub mod routes {
             self.delete.replace("{uuid}", campaign_id)
         }
 
+        pub fn get_members_route(&self, campaign_id: &str) -> String {
+            self.members.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_remove_member_route(&self, campaign_id: &str) -> String {
+            self.remove_member.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_restore_route(&self, campaign_id: &str) -> String {
+            self.restore.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_recommend_route(&self, campaign_id: &str) -> String {
+            self.recommend.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_levels_route(&self, campaign_id: &str) -> String {
+            self.levels.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_review_route(&self, campaign_id: &str) -> String {
+            self.review.replace("{uuid}", campaign_id)
+        }
+
+        pub fn get_chart_route(&self, campaign_id: &str, chart: &str) -> String {
+            self.chart
+                .replace("{uuid}", campaign_id)
+                .replace("{chart}", chart)
+        }
+
         pub fn get_bench_route(&self, campaign_id: &str) -> String {
             self.bench.replace("{uuid}", campaign_id)
         }
@@ -98,22 +161,11 @@ pub mod routes {
             campaign_id: &str,
             modifier: Option<ResultsPage>,
         ) -> String {
-            let mut res = self.results.replace("{uuid}", campaign_id);
-            if let Some(modifier) = modifier {
-                let page = modifier.page();
-                if page != 0 {
-                    res = format!("{res}?page={page}");
-                }
-
-                if let Some(bench_type) = modifier.bench_type {
-                    if page != 0 {
-                        res = format!("{res}&bench_type={}", bench_type.to_string());
-                    } else {
-                        res = format!("{res}?bench_type={}", bench_type.to_string());
-                    }
-                }
+            let res = self.results.replace("{uuid}", campaign_id);
+            match modifier.and_then(|m| m.query_string()) {
+                Some(query) => format!("{res}?{query}"),
+                None => res,
             }
-            res
         }
 
         pub const fn get_sitemap() -> [&'static str; 2] {
@@ -128,8 +180,15 @@ pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
     about::services(cfg);
     new::services(cfg);
     bench::services(cfg);
+    charts::services(cfg);
     delete::services(cfg);
+    levels::services(cfg);
+    members::services(cfg);
     results::services(cfg);
+    receipt::services(cfg);
+    recommend::services(cfg);
+    review::services(cfg);
+    trash::services(cfg);
 }
 
 pub use super::*;
@@ -147,6 +206,8 @@ pub struct TemplateCampaign {
     pub uuid: String,
     pub route: String,
     pub results: String,
+    pub members: String,
+    pub role: Option<CampaignRole>,
 }
 
 impl From<ListCampaignResp> for TemplateCampaign {
@@ -156,6 +217,7 @@ impl From<ListCampaignResp> for TemplateCampaign {
             .panel
             .campaigns
             .get_results_route(&c.uuid, None);
+        let members = crate::PAGES.panel.campaigns.get_members_route(&c.uuid);
         let uuid = c.uuid;
         let name = c.name;
         Self {
@@ -163,6 +225,8 @@ impl From<ListCampaignResp> for TemplateCampaign {
             name,
             uuid,
             results,
+            members,
+            role: c.role,
         }
     }
 }
@@ -23,6 +23,7 @@ use actix_web::{web, HttpResponse, Responder};
 use tera::Context;
 
 use crate::api::v1::admin::campaigns::{runners, AddCapmaign};
+use crate::api::v1::admin::templates::{self, CampaignTemplate};
 use crate::errors::*;
 use crate::AppData;
 
@@ -44,12 +45,33 @@ impl CtxError for NewCampaign {
     }
 }
 
+/// templates that the form can be pre-filled from
+pub const TEMPLATES_KEY: &str = "templates";
+/// template that the form is pre-filled with
+pub const TEMPLATE_KEY: &str = "template";
+
 impl NewCampaign {
     pub fn new(settings: &Settings) -> Self {
         let ctx = RefCell::new(context(settings, "Login"));
         Self { ctx }
     }
 
+    pub fn with_templates(
+        settings: &Settings,
+        templates: &[CampaignTemplate],
+        selected: Option<&CampaignTemplate>,
+    ) -> Self {
+        let page = Self::new(settings);
+        {
+            let mut ctx = page.ctx.borrow_mut();
+            ctx.insert(TEMPLATES_KEY, templates);
+            if let Some(selected) = selected {
+                ctx.insert(TEMPLATE_KEY, selected);
+            }
+        }
+        page
+    }
+
     pub fn render(&self) -> String {
         TEMPLATES
             .render(NEW_CAMPAIGN.name, &self.ctx.borrow())
@@ -61,28 +83,77 @@ impl NewCampaign {
     path = "PAGES.panel.campaigns.new",
     wrap = "crate::pages::get_page_check_login()"
 )]
-#[tracing::instrument(name = "New campaign form", skip(data))]
-pub async fn new_campaign(data: AppData) -> PageResult<impl Responder, NewCampaign> {
-    let new_campaign = NewCampaign::new(&data.settings).render();
+#[tracing::instrument(name = "New campaign form", skip(data, id))]
+pub async fn new_campaign(
+    id: Identity,
+    query: web::Query<TemplateQuery>,
+    data: AppData,
+) -> PageResult<impl Responder, NewCampaign> {
+    let username = id.identity().unwrap();
+    let templates = templates::runners::list(&username, &data)
+        .await
+        .map_err(|e| PageError::new(NewCampaign::new(&data.settings), e))?;
+    let selected = match &query.template {
+        Some(name) => match templates.iter().find(|t| &t.name == name) {
+            Some(t) => Some(t),
+            None => {
+                return Err(PageError::new(
+                    NewCampaign::with_templates(&data.settings, &templates, None),
+                    ServiceError::TemplateDoesntExist,
+                ))
+            }
+        },
+        None => None,
+    };
+    let new_campaign =
+        NewCampaign::with_templates(&data.settings, &templates, selected).render();
     let html = ContentType::html();
     Ok(HttpResponse::Ok().content_type(html).body(new_campaign))
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateQuery {
+    /// name of the template to pre-fill the form with
+    pub template: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FormAddCampaign {
     pub name: String,
     pub difficulties: String,
+    pub repetitions: i32,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub instructions: String,
+    /// when set, the campaign's settings are also saved as a template of this name
+    #[serde(default)]
+    pub save_as_template: String,
 }
 
 impl FormAddCampaign {
-    fn parse(self) -> ServiceResult<AddCapmaign> {
+    fn parse(self) -> ServiceResult<(AddCapmaign, Option<String>)> {
         let name = self.name;
+        let optional = |text: String| {
+            if text.trim().is_empty() {
+                None
+            } else {
+                Some(text)
+            }
+        };
         let mut difficulties = Vec::new();
         for d in self.difficulties.split(',') {
             let d = d.parse::<i32>().map_err(|_| ServiceError::NotANumber)?;
             difficulties.push(d);
         }
-        Ok(AddCapmaign { name, difficulties })
+        let campaign = AddCapmaign {
+            name,
+            difficulties,
+            repetitions: self.repetitions,
+            description: optional(self.description),
+            instructions: optional(self.instructions),
+        };
+        Ok((campaign, optional(self.save_as_template)))
     }
 }
 
@@ -97,11 +168,24 @@ pub async fn new_campaign_submit(
     data: AppData,
 ) -> PageResult<impl Responder, NewCampaign> {
     let username = id.identity().unwrap();
-    let mut payload = payload
+    let (mut payload, template_name) = payload
         .into_inner()
         .parse()
         .map_err(|e| PageError::new(NewCampaign::new(&data.settings), e))?;
 
+    if let Some(name) = template_name {
+        let mut template = CampaignTemplate {
+            name,
+            difficulties: payload.difficulties.clone(),
+            repetitions: payload.repetitions,
+            description: payload.description.clone(),
+            instructions: payload.instructions.clone(),
+        };
+        templates::runners::add(&username, &mut template, &data)
+            .await
+            .map_err(|e| PageError::new(NewCampaign::new(&data.settings), e))?;
+    }
+
     runners::add_runner(&username, &mut payload, &data)
         .await
         .map_err(|e| PageError::new(NewCampaign::new(&data.settings), e))?;
@@ -134,6 +218,7 @@ mod tests {
         const PASSWORD: &str = "longpassword";
 
         const CAMPAIGN_NAME: &str = "testcampaignuser";
+        const TEMPLATE_NAME: &str = "testcampaigntemplate";
 
         let data = get_test_data().await;
         let app = get_app!(data).await;
@@ -153,12 +238,16 @@ mod tests {
         let new = super::FormAddCampaign {
             name: CAMPAIGN_NAME.into(),
             difficulties,
+            repetitions: 3,
+            description: "Takes *about* 5 minutes".into(),
+            instructions: String::new(),
+            save_as_template: TEMPLATE_NAME.into(),
         };
 
         let new_resp = test::call_service(
             &app,
             post_request!(&new, crate::PAGES.panel.campaigns.new, FORM)
-                .cookie(cookies)
+                .cookie(cookies.clone())
                 .to_request(),
         )
         .await;
@@ -169,5 +258,23 @@ mod tests {
             headers.get(header::LOCATION).unwrap(),
             PAGES.panel.campaigns.home,
         );
+
+        let resp = get_request!(
+            &app,
+            &format!("{}?template={TEMPLATE_NAME}", PAGES.panel.campaigns.new),
+            cookies.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(&format!("value=\"{}\"", new.difficulties)));
+        assert!(body.contains("value=\"3\""));
+        assert!(body.contains(&new.description));
+
+        let resp = get_request!(
+            &app,
+            &format!("{}?template=doesntexist", PAGES.panel.campaigns.new),
+            cookies
+        );
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 }
@@ -0,0 +1,186 @@
+/*
+ * Copyright (C) 2021  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use tera::Context;
+
+use crate::api::v1::bench::{runners, SubmissionProof, SubmissionReceipt};
+use crate::settings::Settings;
+use crate::AppData;
+
+pub use super::*;
+
+pub struct Receipt {
+    ctx: RefCell<Context>,
+}
+
+pub const RECEIPT: TemplateFile =
+    TemplateFile::new("submission_receipt", "panel/campaigns/receipt.html");
+
+const DELETED_KEY: &str = "deleted";
+
+impl CtxError for Receipt {
+    fn with_error(&self, e: &ReadableError) -> String {
+        self.ctx.borrow_mut().insert(ERROR_KEY, e);
+        self.render()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ReceiptPayload {
+    pub proof: SubmissionProof,
+    pub receipt: SubmissionReceipt,
+}
+
+impl Receipt {
+    pub fn new(settings: &Settings, payload: Option<ReceiptPayload>) -> Self {
+        let ctx = RefCell::new(context(settings, "Your submission"));
+        if let Some(payload) = payload {
+            ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+        }
+        Self { ctx }
+    }
+
+    pub fn deleted(settings: &Settings) -> Self {
+        let s = Self::new(settings, None);
+        s.ctx.borrow_mut().insert(DELETED_KEY, &true);
+        s
+    }
+
+    pub fn render(&self) -> String {
+        TEMPLATES.render(RECEIPT.name, &self.ctx.borrow()).unwrap()
+    }
+}
+
+#[actix_web_codegen_const_routes::get(path = "PAGES.panel.campaigns.receipt")]
+pub async fn receipt(data: AppData) -> PageResult<impl Responder, Receipt> {
+    let page = Receipt::new(&data.settings, None).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+#[actix_web_codegen_const_routes::post(path = "PAGES.panel.campaigns.receipt")]
+pub async fn receipt_submit(
+    data: AppData,
+    payload: web::Form<SubmissionProof>,
+) -> PageResult<impl Responder, Receipt> {
+    let proof = payload.into_inner();
+    let receipt = runners::get_receipt(&proof, &data)
+        .await
+        .map_err(|e| PageError::new(Receipt::new(&data.settings, None), e))?;
+
+    let payload = ReceiptPayload { proof, receipt };
+    let page = Receipt::new(&data.settings, Some(payload)).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+#[actix_web_codegen_const_routes::post(path = "PAGES.panel.campaigns.delete_submission")]
+pub async fn delete_submission(
+    data: AppData,
+    payload: web::Form<SubmissionProof>,
+) -> PageResult<impl Responder, Receipt> {
+    runners::delete_submission(&payload, &data)
+        .await
+        .map_err(|e| PageError::new(Receipt::new(&data.settings, None), e))?;
+
+    let page = Receipt::deleted(&data.settings).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(receipt);
+    cfg.service(receipt_submit);
+    cfg.service(delete_submission);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+
+    use crate::api::v1::bench::{Submission, SubmissionType};
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+    use actix_web::http::StatusCode;
+
+    #[actix_rt::test]
+    async fn receipt_page_works() {
+        const NAME: &str = "receiptpageuser";
+        const EMAIL: &str = "receiptpageuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const DEVICE_USER_PROVIDED: &str = "receiptpagedevice";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+
+        let campaign = create_new_campaign(NAME, data.clone(), cookies.clone()).await;
+        let submit_payload = Submission {
+            device_user_provided: DEVICE_USER_PROVIDED.into(),
+            device_software_recognised: "receiptpageuser.v2".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        let proof =
+            submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        let resp = get_request!(&app, PAGES.panel.campaigns.receipt);
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&proof, PAGES.panel.campaigns.receipt, FORM).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(DEVICE_USER_PROVIDED));
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&proof, PAGES.panel.campaigns.delete_submission, FORM)
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&proof, PAGES.panel.campaigns.receipt, FORM).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
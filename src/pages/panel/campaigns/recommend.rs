@@ -0,0 +1,259 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use actix_identity::Identity;
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use tera::Context;
+
+use crate::api::v1::admin::bench_types;
+use crate::api::v1::admin::members::{self, CampaignRole};
+use crate::api::v1::admin::stats::{
+    runners, seconds, Recommendation, RecommendationTarget,
+};
+use crate::api::v1::bench::SubmissionType;
+use crate::errors::*;
+use crate::AppData;
+
+pub use super::*;
+
+pub struct Recommend {
+    ctx: RefCell<Context>,
+}
+
+pub const RECOMMEND: TemplateFile =
+    TemplateFile::new("campaign_recommend", "panel/campaigns/recommend.html");
+
+impl CtxError for Recommend {
+    fn with_error(&self, e: &ReadableError) -> String {
+        self.ctx.borrow_mut().insert(ERROR_KEY, e);
+        self.render()
+    }
+}
+
+/// target form, as submitted. Empty fields fall back to the defaults.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecommendQuery {
+    pub percentile: Option<String>,
+    pub max_duration: Option<String>,
+    pub bench_type: Option<String>,
+}
+
+impl RecommendQuery {
+    fn parse(&self) -> ServiceResult<RecommendationTarget> {
+        let number = |v: &Option<String>, default: f64| match v.as_deref().map(str::trim)
+        {
+            None | Some("") => Ok(default),
+            Some(v) => v
+                .parse()
+                .map_err(|_| ServiceError::InvalidRecommendationTarget),
+        };
+        let target = RecommendationTarget {
+            percentile: number(&self.percentile, DEFAULT_PERCENTILE)?,
+            max_duration: seconds::to_millis(number(
+                &self.max_duration,
+                DEFAULT_MAX_DURATION,
+            )?),
+            bench_type: self
+                .bench_type
+                .as_deref()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(SubmissionType::new),
+        };
+        target.validate()?;
+        Ok(target)
+    }
+}
+
+pub const DEFAULT_PERCENTILE: f64 = 95.0;
+/// seconds
+pub const DEFAULT_MAX_DURATION: f64 = 2.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecommendPagePayload {
+    pub percentile: String,
+    pub max_duration: String,
+    pub bench_type: Option<String>,
+    pub bench_types: Vec<String>,
+    pub results: String,
+    pub levels: String,
+    /// `None` until the admin submits a target
+    pub recommendation: Option<Recommendation>,
+}
+
+impl Recommend {
+    pub fn new(settings: &Settings, payload: Option<RecommendPagePayload>) -> Self {
+        let ctx = RefCell::new(context(settings, "Recommend difficulty"));
+        if let Some(payload) = payload {
+            ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+        }
+        Self { ctx }
+    }
+
+    pub fn render(&self) -> String {
+        TEMPLATES
+            .render(RECOMMEND.name, &self.ctx.borrow())
+            .unwrap()
+    }
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "PAGES.panel.campaigns.recommend",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn recommend(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    query: web::Query<RecommendQuery>,
+    data: AppData,
+) -> PageResult<impl Responder, Recommend> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    members::runners::require_role(&username, &uuid, CampaignRole::Viewer, &data)
+        .await
+        .map_err(|e| PageError::new(Recommend::new(&data.settings, None), e))?;
+    let bench_types = bench_types::runners::list(&data)
+        .await
+        .map_err(|e| PageError::new(Recommend::new(&data.settings, None), e))?;
+
+    let mut payload = RecommendPagePayload {
+        percentile: query
+            .percentile
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PERCENTILE.to_string()),
+        max_duration: query
+            .max_duration
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MAX_DURATION.to_string()),
+        bench_type: query.bench_type.clone(),
+        bench_types: bench_types
+            .into_iter()
+            .map(|t| t.name.to_string())
+            .collect(),
+        results: PAGES
+            .panel
+            .campaigns
+            .get_results_route(&uuid.to_string(), None),
+        levels: PAGES.panel.campaigns.get_levels_route(&uuid.to_string()),
+        recommendation: None,
+    };
+
+    // the form is submitted to this page, so a target is only set once it is
+    if query.percentile.is_some() || query.max_duration.is_some() {
+        let res = match query.parse() {
+            Ok(target) => runners::recommend(&uuid, &target, &data).await,
+            Err(e) => Err(e),
+        };
+        match res {
+            Ok(recommendation) => payload.recommendation = Some(recommendation),
+            Err(e) => {
+                return Err(PageError::new(
+                    Recommend::new(&data.settings, Some(payload)),
+                    e,
+                ))
+            }
+        }
+    }
+
+    let page = Recommend::new(&data.settings, Some(payload)).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(recommend);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use actix_web::ResponseError;
+
+    use super::*;
+    use crate::api::v1::bench::Submission;
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+
+    #[actix_rt::test]
+    async fn recommend_page_works() {
+        const NAME: &str = "recommendpageuser";
+        const EMAIL: &str = "recommendpageuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const CAMPAIGN_NAME: &str = "recommendpageusercampaign";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+
+        let campaign =
+            create_new_campaign(CAMPAIGN_NAME, data.clone(), cookies.clone()).await;
+        let route = PAGES
+            .panel
+            .campaigns
+            .get_recommend_route(&campaign.campaign_id);
+
+        let resp = get_request!(&app, &route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let submit_payload = Submission {
+            device_user_provided: "foo".into(),
+            device_software_recognised: "recommendpageuser".into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &SLOW_BENCHES,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        let resp = get_request!(
+            &app,
+            &format!("{route}?percentile=95&max_duration=3&bench_type="),
+            cookies.clone()
+        );
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("<b>Recommended difficulty factor:</b> 3"));
+
+        let resp = get_request!(
+            &app,
+            &format!("{route}?percentile=foo&max_duration=3"),
+            cookies.clone()
+        );
+        assert_eq!(
+            resp.status(),
+            ServiceError::InvalidRecommendationTarget.status_code()
+        );
+    }
+}
@@ -15,16 +15,25 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::str::FromStr;
 
 use actix_web::http::header::ContentType;
 use actix_web::{web, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
+use sqlx::types::time::{Date, Month, OffsetDateTime};
 use sqlx::types::Uuid;
 use tera::Context;
 
-use crate::api::v1::admin::campaigns::{runners, ResultsPage, SurveyResponse};
-use crate::errors::ServiceError;
+use super::charts::{self, ChartData, RenderedChart};
+use crate::api::v1::admin::bench_types::{self, BenchType};
+use crate::api::v1::admin::campaigns::{
+    runners, ResultsPage, SurveyResponse, SurveyResults,
+};
+use crate::api::v1::admin::review::ReviewState;
+use crate::api::v1::admin::stats::{self, CampaignHashrate, CampaignStats};
+use crate::api::v1::bench::SubmissionType;
+use crate::errors::{ServiceError, ServiceResult};
 use crate::settings::Settings;
 use crate::AppData;
 
@@ -46,86 +55,184 @@ impl CtxError for CampaignResults {
 
 const RESUTS_LIMIT: usize = 10;
 
+/// query of the results page. Unlike [ResultsPage], it tolerates the search form's
+/// empty fields and takes dates (`YYYY-MM-DD`) as well as UNIX timestamps.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ResultsQuery {
+    pub after: Option<String>,
+    pub bench_type: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub min_threads: Option<String>,
+    pub max_threads: Option<String>,
+    pub device: Option<String>,
+    pub review: Option<String>,
+}
+
+impl ResultsQuery {
+    pub fn parse(self) -> ServiceResult<ResultsPage> {
+        let threads = |t: Option<String>| {
+            non_empty(t)
+                .map(|t| t.parse())
+                .transpose()
+                .map_err(|_| ServiceError::InvalidResultsFilter)
+        };
+
+        Ok(ResultsPage {
+            after: non_empty(self.after),
+            bench_type: non_empty(self.bench_type).map(SubmissionType::new),
+            from: parse_time(self.from, false)?,
+            // dates are inclusive, so `to` is the end of the day
+            to: parse_time(self.to, true)?,
+            min_threads: threads(self.min_threads)?,
+            max_threads: threads(self.max_threads)?,
+            device: non_empty(self.device),
+            review: non_empty(self.review)
+                .map(|r| ReviewState::from_str(&r))
+                .transpose()
+                .map_err(|_| ServiceError::InvalidResultsFilter)?,
+        })
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_owned()).filter(|v| !v.is_empty())
+}
+
+fn parse_time(value: Option<String>, end_of_day: bool) -> ServiceResult<Option<i64>> {
+    let value = match non_empty(value) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    if let Ok(timestamp) = value.parse() {
+        return Ok(Some(timestamp));
+    }
+
+    let parse_date = || {
+        let mut parts = value.splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month: u8 = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        let date =
+            Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()?;
+        if end_of_day {
+            date.next_day()
+        } else {
+            Some(date)
+        }
+    };
+    parse_date()
+        .map(|d| Some(d.midnight().assume_utc().unix_timestamp()))
+        .ok_or(ServiceError::InvalidResultsFilter)
+}
+
+/// `YYYY-MM-DD` date of a UNIX timestamp, for the search form's date fields
+fn format_date(timestamp: Option<i64>, end_of_day: bool) -> Option<String> {
+    let timestamp = if end_of_day {
+        timestamp? - 1
+    } else {
+        timestamp?
+    };
+    OffsetDateTime::from_unix_timestamp(timestamp)
+        .ok()
+        .map(|t| t.date().to_string())
+}
+
+/// link to the results page filtered by a benchmark type
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct BenchTypeFilter {
+    pub name: String,
+    /// `None` when the filter is already applied
+    pub route: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct ResultsPagePayload {
     next_page: Option<String>,
     submissions: Vec<SurveyResponse>,
-    pub wasm_only: Option<String>,
-    pub js_only: Option<String>,
+    pub filters: Vec<BenchTypeFilter>,
     pub all_benches: Option<String>,
+    /// filters currently applied, to pre-fill the search form
+    pub query: ResultsPage,
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    /// results page without any filters
+    pub clear_filters: String,
+    /// summary of all of the campaign's responses, regardless of filters
+    pub stats: CampaignStats,
+    pub hashrate: CampaignHashrate,
+    pub recommend: String,
+    pub levels: String,
+    /// queue of flagged submissions
+    pub review: String,
+    /// device aliases, which normalise `device_user_provided`
+    pub devices: String,
+    pub charts: Vec<RenderedChart>,
 }
 
 impl ResultsPagePayload {
     pub fn new(
-        submissions: Vec<SurveyResponse>,
+        results: SurveyResults,
         campaign_id: &Uuid,
         modifier: ResultsPage,
+        bench_types: Vec<BenchType>,
+        stats: CampaignStats,
+        hashrate: CampaignHashrate,
+        charts: Vec<RenderedChart>,
     ) -> Self {
         let campaign_id_str = campaign_id.to_string();
 
-        let all_benches;
-        let wasm_only;
-        let js_only;
-        match modifier.bench_type {
-            Some(SubmissionType::Js) => {
-                all_benches = Some(
-                    crate::PAGES
-                        .panel
-                        .campaigns
-                        .get_results_route(&campaign_id_str, None),
-                );
-
-                wasm_only = Some(crate::PAGES.panel.campaigns.get_results_route(
-                    &campaign_id_str,
-                    Some(ResultsPage::new(None, Some(SubmissionType::Wasm))),
-                ));
-                js_only = None;
-            }
-            Some(SubmissionType::Wasm) => {
-                js_only = Some(crate::PAGES.panel.campaigns.get_results_route(
-                    &campaign_id_str,
-                    Some(ResultsPage::new(None, Some(SubmissionType::Js))),
-                ));
-                all_benches = Some(
-                    crate::PAGES
-                        .panel
-                        .campaigns
-                        .get_results_route(&campaign_id_str, None),
-                );
-                wasm_only = None;
-            }
-            None => {
-                all_benches = None;
-                js_only = Some(crate::PAGES.panel.campaigns.get_results_route(
-                    &campaign_id_str,
-                    Some(ResultsPage::new(None, Some(SubmissionType::Js))),
-                ));
-                wasm_only = Some(crate::PAGES.panel.campaigns.get_results_route(
-                    &campaign_id_str,
-                    Some(ResultsPage::new(None, Some(SubmissionType::Wasm))),
-                ));
-            }
-        }
+        let all_benches = modifier.bench_type.as_ref().map(|_| {
+            crate::PAGES.panel.campaigns.get_results_route(
+                &campaign_id_str,
+                Some(modifier.with_bench_type(None)),
+            )
+        });
 
-        let next_page = if submissions.len() == RESUTS_LIMIT {
-            let m = ResultsPage::new(Some(modifier.page() + 1), modifier.bench_type);
+        let filters = bench_types
+            .into_iter()
+            .map(|t| {
+                let route = if modifier.bench_type.as_ref() == Some(&t.name) {
+                    None
+                } else {
+                    Some(crate::PAGES.panel.campaigns.get_results_route(
+                        &campaign_id_str,
+                        Some(modifier.with_bench_type(Some(t.name.clone()))),
+                    ))
+                };
+                BenchTypeFilter {
+                    name: t.name.to_string(),
+                    route,
+                }
+            })
+            .collect();
 
-            Some(
-                PAGES
-                    .panel
-                    .campaigns
-                    .get_results_route(&campaign_id_str, Some(m)),
+        let next_page = results.next.map(|next| {
+            PAGES.panel.campaigns.get_results_route(
+                &campaign_id_str,
+                Some(modifier.with_after(Some(next))),
             )
-        } else {
-            None
-        };
+        });
 
         Self {
             next_page,
-            submissions,
-            js_only,
-            wasm_only,
+            submissions: results.responses,
+            filters,
             all_benches,
+            clear_filters: PAGES
+                .panel
+                .campaigns
+                .get_results_route(&campaign_id_str, None),
+            from_date: format_date(modifier.from, false),
+            to_date: format_date(modifier.to, true),
+            query: modifier.with_after(None),
+            stats,
+            hashrate,
+            recommend: PAGES.panel.campaigns.get_recommend_route(&campaign_id_str),
+            levels: PAGES.panel.campaigns.get_levels_route(&campaign_id_str),
+            review: PAGES.panel.campaigns.get_review_route(&campaign_id_str),
+            devices: PAGES.panel.devices.to_string(),
+            charts,
         }
     }
 }
@@ -151,7 +258,7 @@ pub async fn results(
     id: Identity,
     data: AppData,
     path: web::Path<String>,
-    query: web::Query<ResultsPage>,
+    query: web::Query<ResultsQuery>,
 ) -> PageResult<impl Responder, CampaignResults> {
     match Uuid::from_str(&path) {
         Err(_) => Err(PageError::new(
@@ -160,22 +267,42 @@ pub async fn results(
         )),
         Ok(uuid) => {
             let username = id.identity().unwrap();
-            let query = query.into_inner();
-            let page = query.page();
+            let query = query.into_inner().parse().map_err(|e| {
+                PageError::new(CampaignResults::new(&data.settings, None), e)
+            })?;
 
-            let results = runners::get_results(
-                &username,
-                &uuid,
-                &data,
-                page,
-                RESUTS_LIMIT,
-                query.bench_type.clone(),
-            )
-            .await
-            .map_err(|e| {
+            let results =
+                runners::get_results(&username, &uuid, &data, &query, RESUTS_LIMIT)
+                    .await
+                    .map_err(|e| {
+                        PageError::new(CampaignResults::new(&data.settings, None), e)
+                    })?;
+            let bench_types = bench_types::runners::list(&data).await.map_err(|e| {
                 PageError::new(CampaignResults::new(&data.settings, None), e)
             })?;
-            let payload = ResultsPagePayload::new(results, &uuid, query);
+            // get_results already checked that the admin can view the campaign
+            let stats = stats::runners::summary(&uuid, &data).await.map_err(|e| {
+                PageError::new(CampaignResults::new(&data.settings, None), e)
+            })?;
+            let hashrate =
+                stats::runners::hashrate(&uuid, &data).await.map_err(|e| {
+                    PageError::new(CampaignResults::new(&data.settings, None), e)
+                })?;
+            let chart_data = ChartData::fetch(&uuid, stats.clone(), &data)
+                .await
+                .map_err(|e| {
+                    PageError::new(CampaignResults::new(&data.settings, None), e)
+                })?;
+            let charts = charts::render_all(&uuid, &chart_data);
+            let payload = ResultsPagePayload::new(
+                results,
+                &uuid,
+                query,
+                bench_types,
+                stats,
+                hashrate,
+                charts,
+            );
 
             let results_page =
                 CampaignResults::new(&data.settings, Some(payload)).render();
@@ -0,0 +1,278 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use actix_identity::Identity;
+use actix_web::http::header;
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use tera::Context;
+
+use crate::api::v1::admin::campaigns::SurveyResponse;
+use crate::api::v1::admin::members::{self, CampaignRole};
+use crate::api::v1::admin::review::{runners, QueueQuery, ReviewDecision};
+use crate::errors::*;
+use crate::AppData;
+
+pub use super::*;
+
+pub struct ReviewQueue {
+    ctx: RefCell<Context>,
+}
+
+pub const REVIEW_QUEUE: TemplateFile =
+    TemplateFile::new("review_queue", "panel/campaigns/review.html");
+
+impl CtxError for ReviewQueue {
+    fn with_error(&self, e: &ReadableError) -> String {
+        self.ctx.borrow_mut().insert(ERROR_KEY, e);
+        self.render()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewPagePayload {
+    /// flagged submissions, oldest first
+    pub submissions: Vec<SurveyResponse>,
+    pub next_page: Option<String>,
+    /// `Some` when the admin can accept and exclude submissions
+    pub decide_url: Option<String>,
+    pub results: String,
+}
+
+impl ReviewQueue {
+    pub fn new(settings: &Settings, payload: Option<ReviewPagePayload>) -> Self {
+        let ctx = RefCell::new(context(settings, "Review"));
+        if let Some(payload) = payload {
+            ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+        }
+        Self { ctx }
+    }
+
+    pub fn render(&self) -> String {
+        TEMPLATES
+            .render(REVIEW_QUEUE.name, &self.ctx.borrow())
+            .unwrap()
+    }
+}
+
+async fn get_payload(
+    username: &str,
+    uuid: &Uuid,
+    after: Option<String>,
+    data: &AppData,
+) -> ServiceResult<ReviewPagePayload> {
+    let role =
+        members::runners::require_role(username, uuid, CampaignRole::Viewer, data)
+            .await?;
+    let queue = runners::queue(username, uuid, after, data).await?;
+    let campaign_id = uuid.to_string();
+    let review_route = PAGES.panel.campaigns.get_review_route(&campaign_id);
+    let next_page = queue
+        .next
+        .map(|next| format!("{review_route}?after={}", urlencoding::encode(&next)));
+    let decide_url = if role >= CampaignRole::Editor {
+        Some(review_route)
+    } else {
+        None
+    };
+    Ok(ReviewPagePayload {
+        submissions: queue.responses,
+        next_page,
+        decide_url,
+        results: PAGES.panel.campaigns.get_results_route(&campaign_id, None),
+    })
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "PAGES.panel.campaigns.review",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn review(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    query: web::Query<QueueQuery>,
+    data: AppData,
+) -> PageResult<impl Responder, ReviewQueue> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    let payload = get_payload(&username, &uuid, query.into_inner().after, &data)
+        .await
+        .map_err(|e| PageError::new(ReviewQueue::new(&data.settings, None), e))?;
+
+    let page = ReviewQueue::new(&data.settings, Some(payload)).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "PAGES.panel.campaigns.review",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn decide(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    payload: web::Form<ReviewDecision>,
+    data: AppData,
+) -> PageResult<impl Responder, ReviewQueue> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    let res = match members::runners::require_role(
+        &username,
+        &uuid,
+        CampaignRole::Editor,
+        &data,
+    )
+    .await
+    {
+        Ok(_) => runners::decide(&uuid, &payload, &data).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        let payload = get_payload(&username, &uuid, None, &data).await.ok();
+        return Err(PageError::new(ReviewQueue::new(&data.settings, payload), e));
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((
+            header::LOCATION,
+            PAGES.panel.campaigns.get_review_route(&uuid.to_string()),
+        ))
+        .finish())
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(review);
+    cfg.service(decide);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use actix_web::test;
+
+    use super::*;
+
+    use crate::api::v1::admin::review::{Decision, ReviewState};
+    use crate::api::v1::bench::{Bench, Submission, SubmissionType};
+    use crate::api::v1::device::DeviceHints;
+    use crate::tests::*;
+    use crate::*;
+    use actix_web::http::StatusCode;
+
+    #[actix_rt::test]
+    async fn review_page_works() {
+        const NAME: &str = "reviewpageuser";
+        const EMAIL: &str = "reviewpageuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const CAMPAIGN_NAME: &str = "reviewpageusercampaign";
+        const DEVICE: &str = "reviewpagedevice";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+        let survey = get_survey_user(data.clone()).await;
+        let survey_cookie = get_cookie!(survey);
+
+        let campaign =
+            create_new_campaign(CAMPAIGN_NAME, data.clone(), cookies.clone()).await;
+        let review_route = PAGES
+            .panel
+            .campaigns
+            .get_review_route(&campaign.campaign_id);
+
+        let resp = get_request!(&app, &review_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(!body.contains(DEVICE));
+
+        let identical: Vec<Bench> = BENCHES
+            .iter()
+            .map(|b| Bench {
+                difficulty: b.difficulty,
+                duration: 3.0,
+            })
+            .collect();
+        let submit_payload = Submission {
+            device_user_provided: DEVICE.into(),
+            device_software_recognised: DEVICE.into(),
+            threads: 4,
+            benches: solve_benches(
+                &campaign,
+                data.clone(),
+                survey_cookie.clone(),
+                &identical,
+            )
+            .await,
+            submission_type: SubmissionType::wasm(),
+            idempotency_key: None,
+            hints: DeviceHints::default(),
+        };
+        submit_bench(&submit_payload, &campaign, survey_cookie, data.clone()).await;
+
+        let resp = get_request!(&app, &review_route, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(DEVICE));
+        assert!(body.contains("identical_durations"));
+
+        let uuid = Uuid::parse_str(&campaign.campaign_id).unwrap();
+        let queue = runners::queue(NAME, &uuid, None, &AppData::new(data.clone()))
+            .await
+            .unwrap();
+        let accept = ReviewDecision {
+            id: i32::try_from(queue.responses[0].id).unwrap(),
+            decision: Decision::Accept,
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&accept, &review_route, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), &review_route);
+
+        let resp = get_request!(&app, &review_route, cookies.clone());
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(!body.contains(DEVICE));
+
+        let queue = runners::queue(NAME, &uuid, None, &AppData::new(data.clone()))
+            .await
+            .unwrap();
+        assert!(queue.responses.is_empty());
+        let accepted = crate::api::v1::admin::campaigns::runners::get_results(
+            NAME,
+            &uuid,
+            &AppData::new(data.clone()),
+            &Default::default(),
+            10,
+        )
+        .await
+        .unwrap()
+        .responses;
+        assert_eq!(accepted[0].review.state, ReviewState::Accepted);
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use actix_identity::Identity;
+use actix_web::http::header;
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use tera::Context;
+
+use crate::api::v1::admin::campaigns::{runners, TrashedCampaign};
+use crate::AppData;
+
+pub use super::*;
+
+pub struct Trash {
+    ctx: RefCell<Context>,
+}
+
+pub const TRASH: TemplateFile =
+    TemplateFile::new("campaign_trash", "panel/campaigns/trash.html");
+
+/// number of days that trashed campaigns can be restored for
+pub const GRACE_PERIOD_KEY: &str = "grace_period_days";
+
+impl CtxError for Trash {
+    fn with_error(&self, e: &ReadableError) -> String {
+        self.ctx.borrow_mut().insert(ERROR_KEY, e);
+        self.render()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TemplateTrashedCampaign {
+    pub name: String,
+    pub deleted_at: i64,
+    pub purge_at: i64,
+    pub restore: String,
+}
+
+impl From<TrashedCampaign> for TemplateTrashedCampaign {
+    fn from(c: TrashedCampaign) -> Self {
+        let restore = crate::PAGES.panel.campaigns.get_restore_route(&c.uuid);
+        Self {
+            name: c.name,
+            deleted_at: c.deleted_at,
+            purge_at: c.purge_at,
+            restore,
+        }
+    }
+}
+
+impl Trash {
+    pub fn new(
+        settings: &Settings,
+        payload: Option<Vec<TemplateTrashedCampaign>>,
+    ) -> Self {
+        let ctx = RefCell::new(context(settings, "Trash"));
+        ctx.borrow_mut().insert(
+            GRACE_PERIOD_KEY,
+            &(settings.trash.grace_period / (24 * 60 * 60)),
+        );
+        if let Some(payload) = payload {
+            if !payload.is_empty() {
+                ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+            }
+        }
+        Self { ctx }
+    }
+
+    pub fn render(&self) -> String {
+        TEMPLATES.render(TRASH.name, &self.ctx.borrow()).unwrap()
+    }
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "PAGES.panel.campaigns.trash",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn trash(id: Identity, data: AppData) -> PageResult<impl Responder, Trash> {
+    let username = id.identity().unwrap();
+    let mut campaigns = runners::list_trash(&username, &data)
+        .await
+        .map_err(|e| PageError::new(Trash::new(&data.settings, None), e))?;
+    let campaigns = campaigns.drain(0..).map(|c| c.into()).collect();
+
+    let page = Trash::new(&data.settings, Some(campaigns)).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "PAGES.panel.campaigns.restore",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn restore(
+    id: Identity,
+    path: web::Path<uuid::Uuid>,
+    data: AppData,
+) -> PageResult<impl Responder, Trash> {
+    let username = id.identity().unwrap();
+    let uuid = Uuid::parse_str(&path.to_string()).unwrap();
+
+    runners::restore(&username, &uuid, &data)
+        .await
+        .map_err(|e| PageError::new(Trash::new(&data.settings, None), e))?;
+
+    Ok(HttpResponse::Found()
+        .insert_header((header::LOCATION, PAGES.panel.campaigns.home))
+        .finish())
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(trash);
+    cfg.service(restore);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+
+    use super::*;
+
+    use crate::api::v1::admin::auth::runners::Password;
+    use crate::tests::*;
+    use crate::*;
+    use actix_web::http::StatusCode;
+
+    #[actix_rt::test]
+    async fn trash_page_works() {
+        const NAME: &str = "trashpageuser";
+        const EMAIL: &str = "trashpageuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const CAMPAIGN_NAME: &str = "trashpageusercampaign";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+
+        let campaign =
+            create_new_campaign(CAMPAIGN_NAME, data.clone(), cookies.clone()).await;
+
+        let creds = Password {
+            password: PASSWORD.into(),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(
+                &creds,
+                &PAGES
+                    .panel
+                    .campaigns
+                    .get_delete_route(&campaign.campaign_id),
+                FORM
+            )
+            .cookie(cookies.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+
+        let resp = get_request!(&app, PAGES.panel.campaigns.trash, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(CAMPAIGN_NAME));
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&PAGES
+                .panel
+                .campaigns
+                .get_restore_route(&campaign.campaign_id))
+            .cookie(cookies.clone())
+            .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(
+            resp.headers().get(header::LOCATION).unwrap(),
+            PAGES.panel.campaigns.home
+        );
+
+        let resp = get_request!(&app, PAGES.panel.campaigns.trash, cookies.clone());
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(!body.contains(CAMPAIGN_NAME));
+    }
+}
@@ -0,0 +1,262 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use actix_identity::Identity;
+use actix_web::http::header;
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use tera::Context;
+
+use crate::api::v1::admin::devices::{
+    runners, AddDeviceAlias, DeviceAlias, DeviceGroup, RemoveDeviceAlias,
+};
+use crate::api::v1::admin::require_site_admin;
+use crate::errors::*;
+use crate::settings::Settings;
+use crate::AppData;
+
+use super::*;
+pub use crate::pages::errors::*;
+
+pub struct DeviceAliases {
+    ctx: RefCell<Context>,
+}
+
+pub const DEVICE_ALIASES: TemplateFile =
+    TemplateFile::new("device_aliases", "panel/devices.html");
+
+impl CtxError for DeviceAliases {
+    fn with_error(&self, e: &ReadableError) -> String {
+        self.ctx.borrow_mut().insert(ERROR_KEY, e);
+        self.render()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevicesPagePayload {
+    pub aliases: Vec<DeviceAlias>,
+    /// device names, without an alias, that are probably the same device
+    pub suggestions: Vec<DeviceGroup>,
+    pub add_url: String,
+    pub remove_url: String,
+}
+
+impl DeviceAliases {
+    pub fn new(settings: &Settings, payload: Option<DevicesPagePayload>) -> Self {
+        let ctx = RefCell::new(context(settings, "Devices"));
+        if let Some(payload) = payload {
+            ctx.borrow_mut().insert(PAYLOAD_KEY, &payload);
+        }
+        Self { ctx }
+    }
+
+    pub fn render(&self) -> String {
+        TEMPLATES
+            .render(DEVICE_ALIASES.name, &self.ctx.borrow())
+            .unwrap()
+    }
+}
+
+async fn get_payload(
+    username: &str,
+    data: &AppData,
+) -> ServiceResult<DevicesPagePayload> {
+    Ok(DevicesPagePayload {
+        aliases: runners::list(data).await?,
+        suggestions: runners::suggestions(username, data).await?,
+        add_url: PAGES.panel.devices.to_string(),
+        remove_url: PAGES.panel.remove_device_alias.to_string(),
+    })
+}
+
+#[actix_web_codegen_const_routes::get(
+    path = "PAGES.panel.devices",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn devices(
+    id: Identity,
+    data: AppData,
+) -> PageResult<impl Responder, DeviceAliases> {
+    let username = id.identity().unwrap();
+
+    let payload = get_payload(&username, &data)
+        .await
+        .map_err(|e| PageError::new(DeviceAliases::new(&data.settings, None), e))?;
+
+    let page = DeviceAliases::new(&data.settings, Some(payload)).render();
+    let html = ContentType::html();
+    Ok(HttpResponse::Ok().content_type(html).body(page))
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "PAGES.panel.devices",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn add_alias(
+    id: Identity,
+    payload: web::Form<AddDeviceAlias>,
+    data: AppData,
+) -> PageResult<impl Responder, DeviceAliases> {
+    let username = id.identity().unwrap();
+
+    let res = match require_site_admin(&username, &data) {
+        Ok(()) => runners::add(&payload, &data).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        let payload = get_payload(&username, &data).await.ok();
+        return Err(PageError::new(
+            DeviceAliases::new(&data.settings, payload),
+            e,
+        ));
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((header::LOCATION, PAGES.panel.devices))
+        .finish())
+}
+
+#[actix_web_codegen_const_routes::post(
+    path = "PAGES.panel.remove_device_alias",
+    wrap = "crate::pages::get_page_check_login()"
+)]
+pub async fn remove_alias(
+    id: Identity,
+    payload: web::Form<RemoveDeviceAlias>,
+    data: AppData,
+) -> PageResult<impl Responder, DeviceAliases> {
+    let username = id.identity().unwrap();
+
+    let res = match require_site_admin(&username, &data) {
+        Ok(()) => runners::remove(payload.id, &data).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        let payload = get_payload(&username, &data).await.ok();
+        return Err(PageError::new(
+            DeviceAliases::new(&data.settings, payload),
+            e,
+        ));
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((header::LOCATION, PAGES.panel.devices))
+        .finish())
+}
+
+pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(devices);
+    cfg.service(add_alias);
+    cfg.service(remove_alias);
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+    use actix_web::ResponseError;
+
+    use super::*;
+
+    use crate::tests::*;
+    use crate::*;
+    use actix_web::http::StatusCode;
+
+    #[actix_rt::test]
+    async fn device_aliases_page_works() {
+        const NAME: &str = "devicealiasespageuser";
+        const EMAIL: &str = "devicealiasespageuser@aaa.com";
+        const PASSWORD: &str = "longpassword";
+        const CANONICAL: &str = "DeviceAliasesPageUser Tablet";
+
+        let data = get_test_data().await;
+        let app = get_app!(data).await;
+        delete_user(NAME, &data).await;
+        sqlx::query!(
+            "DELETE FROM survey_device_aliases WHERE canonical = $1",
+            CANONICAL
+        )
+        .execute(&data.db)
+        .await
+        .unwrap();
+        let (_, _, signin_resp) = register_and_signin(NAME, EMAIL, PASSWORD).await;
+        let cookies = get_cookie!(signin_resp);
+
+        let add = AddDeviceAlias {
+            pattern: "devicealiasespageuser tablet*".into(),
+            canonical: CANONICAL.into(),
+        };
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, PAGES.panel.devices, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::SiteAdminRequired.status_code());
+
+        let data = get_site_admin_data(NAME).await;
+        let app = get_app!(data).await;
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, PAGES.panel.devices, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(
+            resp.headers().get(header::LOCATION).unwrap(),
+            PAGES.panel.devices
+        );
+
+        let resp = get_request!(&app, PAGES.panel.devices, cookies.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(CANONICAL));
+
+        let resp = test::call_service(
+            &app,
+            post_request!(&add, PAGES.panel.devices, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), ServiceError::DeviceAliasExists.status_code());
+
+        let alias = runners::list(&AppData::new(data.clone()))
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|a| a.canonical == CANONICAL)
+            .unwrap();
+        let remove = RemoveDeviceAlias { id: alias.id };
+        let resp = test::call_service(
+            &app,
+            post_request!(&remove, PAGES.panel.remove_device_alias, FORM)
+                .cookie(cookies.clone())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+
+        let resp = get_request!(&app, PAGES.panel.devices, cookies);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(!body.contains(CANONICAL));
+    }
+}
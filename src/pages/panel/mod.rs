@@ -19,11 +19,12 @@ pub use super::{context, Footer, TemplateFile, PAGES, PAYLOAD_KEY, TEMPLATES};
 use crate::AppData;
 
 mod campaigns;
+mod devices;
 mod export;
 
 pub fn register_templates(t: &mut tera::Tera) {
     campaigns::register_templates(t);
-    for template in [export::EXPORT_CAMPAIGNS].iter() {
+    for template in [export::EXPORT_CAMPAIGNS, devices::DEVICE_ALIASES].iter() {
         template.register(t).expect(template.name);
     }
 }
@@ -36,6 +37,8 @@ pub mod routes {
     pub struct Panel {
         pub home: &'static str,
         pub export: &'static str,
+        pub devices: &'static str,
+        pub remove_device_alias: &'static str,
         pub campaigns: Campaigns,
     }
     impl Panel {
@@ -44,6 +47,8 @@ pub mod routes {
             Panel {
                 home: "/",
                 export: "/export",
+                devices: "/admin/devices",
+                remove_device_alias: "/admin/devices/remove",
                 campaigns,
             }
         }
@@ -59,6 +64,7 @@ pub fn services(cfg: &mut actix_web::web::ServiceConfig) {
     cfg.service(home);
     campaigns::services(cfg);
     export::services(cfg);
+    devices::services(cfg);
 }
 
 #[actix_web_codegen_const_routes::get(path = "PAGES.panel.home")]
@@ -105,6 +105,29 @@ impl Publish {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trash {
+    /// seconds that a deleted campaign can be restored for
+    pub grace_period: u64,
+    /// seconds between purges of campaigns whose grace period has expired
+    pub purge_interval: u64,
+}
+
+/// Rules that submissions are checked against when they are received. Flagged
+/// submissions wait in their campaign's review queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sanity {
+    /// fraction that the median duration may drop by from one difficulty to the
+    /// next higher one
+    pub decrease_tolerance: f32,
+    /// difficulties from which solves faster than `fast_solve_duration` are flagged
+    pub fast_solve_difficulty: i32,
+    /// milliseconds
+    pub fast_solve_duration: f32,
+    /// flag submissions whose benches all took exactly as long
+    pub identical_durations: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub debug: bool,
@@ -116,6 +139,11 @@ pub struct Settings {
     pub default_campaign: String,
     pub footer: Footer,
     pub publish: Publish,
+    pub trash: Trash,
+    pub sanity: Sanity,
+    /// usernames of the admins that manage what all campaigns share, like the
+    /// registered benchmark types
+    pub site_admins: Vec<String>,
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -128,6 +156,20 @@ impl Settings {
         s.set_default("database.pool", 2.to_string())
             .expect("Couldn't get the number of CPUs");
 
+        s.set_default("trash.grace_period", (30 * 24 * 60 * 60).to_string())
+            .unwrap();
+        s.set_default("trash.purge_interval", (60 * 60).to_string())
+            .unwrap();
+
+        s.set_default("sanity.decrease_tolerance", "0.1").unwrap();
+        s.set_default("sanity.fast_solve_difficulty", 10_000.to_string())
+            .unwrap();
+        s.set_default("sanity.fast_solve_duration", "1.0").unwrap();
+        s.set_default("sanity.identical_durations", true.to_string())
+            .unwrap();
+
+        s.set_default("site_admins", Vec::<String>::new()).unwrap();
+
         const CURRENT_DIR: &str = "./config/default.toml";
         const ETC: &str = "/etc/mcaptcha-survey/config.toml";
 
@@ -34,9 +34,12 @@ use sqlx::types::Uuid;
 use super::*;
 use crate::api::v1::admin::{
     auth::runners::{Login, Register},
-    campaigns::{AddCapmaign, AddCapmaignResp, ListCampaignResp},
+    campaigns::{AddCapmaign, AddCapmaignResp, ListCampaignResp, DEFAULT_REPETITIONS},
 };
-use crate::api::v1::bench::{Bench, BenchConfig, Submission, SubmissionProof};
+use crate::api::v1::bench::{
+    Bench, BenchConfig, Submission, SubmissionProof, SubmittedBench,
+};
+use crate::api::v1::pow;
 use crate::data::Data;
 use crate::errors::*;
 use crate::V1_API_ROUTES;
@@ -49,6 +52,13 @@ pub async fn get_test_data() -> Arc<Data> {
     Data::new(settings).await
 }
 
+/// test data in which `name` is a site admin
+pub async fn get_site_admin_data(name: &str) -> Arc<Data> {
+    let mut settings = get_test_data().await.settings.clone();
+    settings.site_admins = vec![name.into()];
+    Data::new(settings).await
+}
+
 #[macro_export]
 macro_rules! get_cookie {
     ($resp:expr) => {
@@ -257,6 +267,9 @@ pub async fn create_new_campaign(
     let new = AddCapmaign {
         name: campaign_name.into(),
         difficulties: DIFFICULTIES.into(),
+        repetitions: DEFAULT_REPETITIONS,
+        description: None,
+        instructions: None,
     };
 
     let app = get_app!(data).await;
@@ -307,6 +320,25 @@ pub async fn get_campaign_config(
     test::read_body_json(new_resp).await
 }
 
+/// solve `campaign`'s PoW challenge, as issued to the participant of `cookies`,
+/// for each of `benches`
+pub async fn solve_benches(
+    campaign: &AddCapmaignResp,
+    data: Arc<Data>,
+    cookies: Cookie<'_>,
+    benches: &[Bench],
+) -> Vec<SubmittedBench> {
+    let config = get_campaign_config(campaign, data, cookies).await;
+    benches
+        .iter()
+        .map(|b| SubmittedBench {
+            duration: b.duration,
+            difficulty: b.difficulty,
+            nonce: pow::solve(&config.salt, &config.phrase, b.difficulty),
+        })
+        .collect()
+}
+
 pub async fn delete_campaign(
     camapign: &AddCapmaignResp,
     data: Arc<Data>,
@@ -481,4 +513,12 @@ lazy_static! {
             duration: 5.00,
         },
     ];
+    /// [BENCHES] at a second per difficulty, for tests of time budgets
+    pub static ref SLOW_BENCHES: Vec<Bench> = BENCHES
+        .iter()
+        .map(|b| Bench {
+            difficulty: b.difficulty,
+            duration: b.duration * 1000.0,
+        })
+        .collect();
 }
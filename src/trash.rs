@@ -0,0 +1,59 @@
+/*
+ * Copyright (C) 2023  Aravinth Manivannan <realaravinth@batsense.net>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, either version 3 of the
+ * License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Background job that permanently deletes campaigns whose trash grace period has
+//! expired
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::oneshot::{self, error::TryRecvError, Sender};
+
+use crate::api::v1::admin::campaigns::runners::purge_trash;
+use crate::{errors::ServiceResult, AppData};
+
+pub async fn init_purge_job(
+    data: AppData,
+) -> ServiceResult<(Sender<bool>, impl Future)> {
+    let (tx, mut rx) = oneshot::channel();
+
+    let job = async move {
+        loop {
+            match rx.try_recv() {
+                Ok(_) => {
+                    log::info!("Killing trash purge loop: received signal");
+                    break;
+                }
+                Err(TryRecvError::Empty) => {
+                    match purge_trash(&data).await {
+                        Ok(0) => (),
+                        Ok(purged) => {
+                            log::info!("Purged {purged} campaigns from the trash")
+                        }
+                        Err(e) => log::error!("Couldn't purge the trash: {e}"),
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(
+                        data.settings.trash.purge_interval,
+                    ))
+                    .await;
+                }
+                Err(TryRecvError::Closed) => break,
+            }
+        }
+    };
+    let job_fut = tokio::spawn(job);
+    Ok((tx, job_fut))
+}
@@ -14,8 +14,15 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
-import { Bench, BenchConfig, Submission, SubmissionProof } from "./types";
+import {
+  Bench,
+  BenchConfig,
+  DeviceHints,
+  Submission,
+  SubmissionProof,
+} from "./types";
 import ROUTES from "../api/v1/routes";
+import VIEWS from "../views/v1/routes";
 import genJsonPaylod from "../utils/genJsonPayload";
 import isBlankString from "../utils/isBlankString";
 import createError from "../components/error/";
@@ -50,6 +57,11 @@ export const index = () => {
   const stats = document.getElementById("stats");
   const CAMPAIGN_ID = window.location.pathname.split("/")[3];
   let deviceName = "";
+  // retrying a submission from this run must not record it twice
+  const idempotencyKey = Array.from(
+    window.crypto.getRandomValues(new Uint8Array(16)),
+    (b) => b.toString(16).padStart(2, "0")
+  ).join("");
 
   const addResult = (perf: Bench) => {
     const row = document.createElement("tr");
@@ -67,6 +79,17 @@ export const index = () => {
     res.push(perf);
   };
 
+  // navigator.deviceMemory and navigator.userAgentData aren't available on all
+  // browsers and aren't part of the DOM typings yet
+  const getDeviceHints = (): DeviceHints => {
+    const nav = window.navigator as any;
+    return {
+      memory: nav.deviceMemory,
+      platform: nav.userAgentData?.platform || nav.platform || undefined,
+      mobile: nav.userAgentData?.mobile,
+    };
+  };
+
   const submitBench = async () => {
     const submission_type = await get_bench_type();
     const payload: Submission = {
@@ -75,6 +98,8 @@ export const index = () => {
       device_software_recognised: window.navigator.userAgent,
       benches: res,
       submission_type,
+      idempotency_key: idempotencyKey,
+      hints: getDeviceHints(),
     };
 
     const resp = await fetch(
@@ -97,6 +122,24 @@ export const index = () => {
       element.appendChild(document.createElement("br"));
       element.appendChild(proof);
       element.appendChild(proofText);
+
+      const receipt = document.createElement("form");
+      receipt.action = VIEWS.receipt;
+      receipt.method = "POST";
+      Object.entries(data).forEach(([name, value]) => {
+        const input = document.createElement("input");
+        input.type = "hidden";
+        input.name = name;
+        input.value = value.toString();
+        receipt.appendChild(input);
+      });
+      const receiptBtn = document.createElement("button");
+      receiptBtn.className = "btn";
+      receiptBtn.type = "submit";
+      receiptBtn.innerText = "View or delete your submission";
+      receipt.appendChild(receiptBtn);
+
+      element.appendChild(receipt);
       document.getElementById("submission-proof").appendChild(element);
     }
   };
@@ -162,7 +205,7 @@ export const index = () => {
 
     const config = await getConfig();
 
-    const iterations = config.difficulties.length;
+    const iterations = config.difficulties.length * config.repetitions;
 
     const counterElement = document.getElementById("counter");
     counterElement.innerText = `${iterations} more to go`;
@@ -178,9 +221,15 @@ export const index = () => {
       }
     };
 
-    config.difficulties.forEach((difficulty_factor) =>
-      worker.postMessage(difficulty_factor)
-    );
+    for (let i = 0; i < config.repetitions; i++) {
+      config.difficulties.forEach((difficulty_factor) =>
+        worker.postMessage({
+          difficulty_factor,
+          salt: config.salt,
+          phrase: config.phrase,
+        })
+      );
+    }
 
     addDeviceInfo();
   };
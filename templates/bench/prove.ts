@@ -10,7 +10,7 @@
  */
 
 import * as p from "@mcaptcha/pow_sha256-polyfill";
-import { PoWConfig, SubmissionType } from "./types";
+import { PoWConfig, SubmissionType, Work } from "./types";
 
 export const get_bench_type = async (): Promise<SubmissionType> => {
   console.log(`Wasm support says ${WasmSupported}`);
@@ -27,25 +27,37 @@ export const get_bench_type = async (): Promise<SubmissionType> => {
  * proove work
  * @param {PoWConfig} config - the proof-of-work configuration using which
  * work needs to be computed
+ * @returns time taken to compute the proof and the nonce that solves it
  * */
-const prove = async (config: PoWConfig): Promise<number> => {
+const prove = async (config: PoWConfig): Promise<Work> => {
   console.log(`Wasm support says ${WasmSupported}`);
   let duration: number;
+  let nonce: number;
   if (WasmSupported) {
     const wasm = await require("@mcaptcha/pow-wasm");
     const t0 = performance.now();
-    wasm.gen_pow(config.salt, config.string, config.difficulty_factor);
+    const work = wasm.gen_pow(
+      config.salt,
+      config.string,
+      config.difficulty_factor
+    );
     const t1 = performance.now();
     duration = t1 - t0;
+    nonce = JSON.parse(work).nonce;
   } else {
     console.log("WASM unsupported, expect delay during proof generation");
     const t0 = performance.now();
 
-    await p.generate_work(config.salt, config.string, config.difficulty_factor);
+    const work = await p.generate_work(
+      config.salt,
+      config.string,
+      config.difficulty_factor
+    );
     const t1 = performance.now();
     duration = t1 - t0;
+    nonce = work.nonce;
   }
-  return duration;
+  return { duration, nonce };
 };
 
 // credits: @jf-bastien on Stack Overflow
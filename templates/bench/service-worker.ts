@@ -18,25 +18,23 @@
 import { Bench, PoWConfig } from "./types";
 import prove from "./prove";
 
-const SALT = "674243647f1c355da8607a8cdda05120d79ca5d1af8b3b49359d056a0a82";
-const PHRASE = "6e2a53dbc7d307970d7ba3c0000221722cb74f1c325137251ce8fa5c2240";
-
 console.debug("worker registered");
 
 onmessage = async (event) => {
   console.debug("message received at worker");
-  const difficulty_factor = parseInt(event.data);
+  const { difficulty_factor, salt, phrase } = event.data;
   const config: PoWConfig = {
-    string: PHRASE,
+    string: phrase,
     difficulty_factor,
-    salt: SALT,
+    salt,
   };
 
-  const duration = await prove(config);
+  const { duration, nonce } = await prove(config);
 
   const msg: Bench = {
     difficulty: difficulty_factor,
     duration,
+    nonce,
   };
   postMessage(msg);
 };
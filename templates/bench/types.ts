@@ -18,6 +18,7 @@
 export type Bench = {
   difficulty: number;
   duration: number;
+  nonce: number;
 };
 
 export type Submission = {
@@ -26,6 +27,14 @@ export type Submission = {
   threads: number;
   benches: Array<Bench>;
   submission_type: SubmissionType;
+  idempotency_key?: string;
+  hints: DeviceHints;
+};
+
+export type DeviceHints = {
+  memory?: number;
+  platform?: string;
+  mobile?: boolean;
 };
 
 export type SubmissionProof = {
@@ -35,6 +44,15 @@ export type SubmissionProof = {
 
 export type BenchConfig = {
   difficulties: Array<number>;
+  repetitions: number;
+  salt: string;
+  phrase: string;
+  version: number;
+};
+
+export type Work = {
+  duration: number;
+  nonce: number;
 };
 
 export type PoWConfig = {
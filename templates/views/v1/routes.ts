@@ -17,6 +17,7 @@
 
 const ROUTES = {
   bench: (key: string): string => `/survey/campaigns/${key}/bench/`,
+  receipt: "/survey/receipt",
 };
 
 export default ROUTES;